@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+use std::io::{ self, Write, BufRead, BufReader };
+
+
+/*
+
+# Console
+
+    The input and output of a driver. `print`, `eprint` and `flush` write into
+it, `input` reads lines from it. By default, it's the terminal:
+
+    ```
+    let driver = Driver::new(source);
+    ```
+
+    ..but a host can supply its own handles, e.g. a text pane of the game and
+scripted input in tests:
+
+    ```
+    let console = Console::new(Box::new(pane), Box::new("Alice\n".as_bytes()));
+
+    let driver = Driver::with_console(source, console);
+    ```
+
+    The console is shared by every driver created while evaluating, so the
+output stays in order.
+
+*/
+
+
+enum Input {
+    Reader(Box<dyn BufRead>),
+    Callback(Box<dyn FnMut() -> Option<String>>),
+}
+
+pub struct Console {
+    output: RefCell<Box<dyn Write>>,
+    error: RefCell<Box<dyn Write>>,
+
+    input: RefCell<Input>,
+}
+
+impl Console {
+    pub fn new(output: Box<dyn Write>, input: Box<dyn BufRead>) -> Console {
+        Console {
+            output: RefCell::new(output),
+            error: RefCell::new(Box::new(io::stderr())),
+
+            input: RefCell::new(Input::Reader(input)),
+        }
+    }
+
+    // [NOTE] `f` returns the next line of input, or `None` at the end of input.
+    //
+    pub fn with_input_fn<F>(output: Box<dyn Write>, f: F) -> Console
+        where F: FnMut() -> Option<String> + 'static {
+
+        Console {
+            output: RefCell::new(output),
+            error: RefCell::new(Box::new(io::stderr())),
+
+            input: RefCell::new(Input::Callback(Box::new(f))),
+        }
+    }
+
+    pub fn std() -> Console {
+        Console::new(Box::new(io::stdout()), Box::new(BufReader::new(io::stdin())))
+    }
+
+    // [NOTE] Replaces standard error as the target of `eprint`.
+    //
+    pub fn error_output(self, error: Box<dyn Write>) -> Console {
+        let mut console = self;
+
+        console.error = RefCell::new(error);
+
+        console
+    }
+
+    pub fn write(&self, s: &str) -> io::Result<()> {
+        self.output.borrow_mut().write_all(s.as_bytes())
+    }
+
+    pub fn write_err(&self, s: &str) -> io::Result<()> {
+        self.error.borrow_mut().write_all(s.as_bytes())
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        match self.output.borrow_mut().flush() {
+            Ok(_) => { self.error.borrow_mut().flush() },
+            Err(e) => { Err(e) },
+        }
+    }
+
+    // [NOTE] Returns the next line without the line break, `None` at the end of input.
+    //
+    pub fn read_line(&self) -> io::Result<Option<String>> {
+        let line = match &mut *self.input.borrow_mut() {
+            Input::Reader(r) => {
+                let mut line = String::new();
+
+                match r.read_line(&mut line) {
+                    Ok(0) => { None },
+                    Ok(_) => { Some(line) },
+
+                    Err(e) => { return Err(e); },
+                }
+            },
+
+            Input::Callback(f) => { f() },
+        };
+
+        Ok(line.map(|l| l.trim_end_matches(['\n', '\r']).to_owned()))
+    }
+}
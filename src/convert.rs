@@ -0,0 +1,508 @@
+use parsing::ast::{ NodeWrapper, NodeKind };
+use parsing::span::Span;
+
+use std::collections::{ HashMap, BTreeMap };
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+
+/*
+
+# Value conversion
+
+    Converts Rust values to Miful values and back, so that host functions can
+work with ordinary Rust types:
+
+    ```
+    driver.register_fn("heal", vec![MifulType::Simple("int".to_owned())], |args| {
+        let amount: i64 = FromMiful::from_miful(&args[0])?;
+
+        Ok(player.heal(amount).into_miful())
+    });
+    ```
+
+    The conversion follows the values of MD2:
+
+* integers <-> int, floats <-> float (an int converts to a float, too)
+* `u64`, `usize` <- int
+* `String`, `&str` <-> str
+* `bool` <-> sym(true), sym(false)
+* `()` -> (obj nil)
+* `Option<T>` <-> (obj nil) for `None`, the value itself for `Some`
+* `Vec<T>` <-> (list)
+* tuples <-> (list) of the same length
+* `HashMap<K, V>`, `BTreeMap<K, V>` <-> (list) of (key value) pairs, sorted by key
+* structs <-> (obj name), with `Object` and `ObjectFields`
+
+    Errors are messages, the same as the ones of host functions.
+
+*/
+
+
+pub trait IntoMiful {
+    fn into_miful(self) -> NodeWrapper;
+}
+
+pub trait FromMiful: Sized {
+    fn from_miful(val: &NodeWrapper) -> Result<Self, String>;
+}
+
+fn expected(t: &str, val: &NodeWrapper) -> String {
+    format!("Expected ` {} `, got ` {} `!", t, val)
+}
+
+
+// [AREA] Values
+//
+
+impl IntoMiful for NodeWrapper {
+    fn into_miful(self) -> NodeWrapper {
+        self
+    }
+}
+
+impl FromMiful for NodeWrapper {
+    fn from_miful(val: &NodeWrapper) -> Result<NodeWrapper, String> {
+        Ok(val.clone())
+    }
+}
+
+macro_rules! int_from_conversion {
+    ($($t:ty),*) => {
+        $(
+            impl FromMiful for $t {
+                fn from_miful(val: &NodeWrapper) -> Result<$t, String> {
+                    if let NodeKind::Int(i) = val.node {
+                        match <$t>::try_from(i) {
+                            Ok(v) => { Ok(v) },
+                            Err(_) => { Err(format!("Int ` {} ` is out of range of ` {} `!", i, stringify!($t))) },
+                        }
+
+                    } else {
+                        Err(expected("int", val))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! int_conversion {
+    ($($t:ty),*) => {
+        $(
+            impl IntoMiful for $t {
+                fn into_miful(self) -> NodeWrapper {
+                    NodeWrapper::new_int(self as i64, Span::default())
+                }
+            }
+
+            int_from_conversion!($t);
+        )*
+    };
+}
+
+int_conversion!(i8, i16, i32, i64, u8, u16, u32);
+
+// [NOTE] Unsigned 64 bit integers above `i64::MAX` don't fit into an int, so
+// `u64` and `usize` only convert from one (checked), not into one. Convert them
+// with `i64::try_from` first.
+//
+int_from_conversion!(u64, usize);
+
+macro_rules! float_conversion {
+    ($($t:ty),*) => {
+        $(
+            impl IntoMiful for $t {
+                fn into_miful(self) -> NodeWrapper {
+                    NodeWrapper::new_float(self as f64, Span::default())
+                }
+            }
+
+            impl FromMiful for $t {
+                fn from_miful(val: &NodeWrapper) -> Result<$t, String> {
+                    match val.node {
+                        NodeKind::Float(f) => { Ok(f as $t) },
+                        NodeKind::Int(i) => { Ok(i as $t) },
+
+                        _ => { Err(expected("float", val)) },
+                    }
+                }
+            }
+        )*
+    };
+}
+
+float_conversion!(f32, f64);
+
+impl IntoMiful for String {
+    fn into_miful(self) -> NodeWrapper {
+        NodeWrapper::new_str(self, Span::default())
+    }
+}
+
+impl IntoMiful for &str {
+    fn into_miful(self) -> NodeWrapper {
+        NodeWrapper::new_str(self.to_owned(), Span::default())
+    }
+}
+
+impl FromMiful for String {
+    fn from_miful(val: &NodeWrapper) -> Result<String, String> {
+        if let NodeKind::Str(s) = &val.node {
+            Ok(s.to_owned())
+
+        } else {
+            Err(expected("str", val))
+        }
+    }
+}
+
+impl IntoMiful for bool {
+    fn into_miful(self) -> NodeWrapper {
+        let sym = if self { "true" } else { "false" };
+
+        NodeWrapper::new_symbol(sym.to_owned(), Span::default())
+    }
+}
+
+impl FromMiful for bool {
+    fn from_miful(val: &NodeWrapper) -> Result<bool, String> {
+        match &val.node {
+            NodeKind::Symbol(s) if s == "true" => { Ok(true) },
+            NodeKind::Symbol(s) if s == "false" => { Ok(false) },
+
+            _ => { Err(expected("(true | false)", val)) },
+        }
+    }
+}
+
+impl IntoMiful for () {
+    fn into_miful(self) -> NodeWrapper {
+        Object::new("nil").build()
+    }
+}
+
+//
+// [END] Values
+
+
+// [AREA] Collections
+//
+
+impl<T: IntoMiful> IntoMiful for Option<T> {
+    fn into_miful(self) -> NodeWrapper {
+        match self {
+            Some(v) => { v.into_miful() },
+            None => { ().into_miful() },
+        }
+    }
+}
+
+impl<T: FromMiful> FromMiful for Option<T> {
+    fn from_miful(val: &NodeWrapper) -> Result<Option<T>, String> {
+        if is_object(val, "nil") {
+            Ok(None)
+
+        } else {
+            match T::from_miful(val) {
+                Ok(v) => { Ok(Some(v)) },
+                Err(e) => { Err(e) },
+            }
+        }
+    }
+}
+
+impl<T: IntoMiful> IntoMiful for Vec<T> {
+    fn into_miful(self) -> NodeWrapper {
+        let elements = self.into_iter().map(IntoMiful::into_miful).collect();
+
+        NodeWrapper::new_list(elements, vec![], Span::default())
+    }
+}
+
+impl<T: FromMiful> FromMiful for Vec<T> {
+    fn from_miful(val: &NodeWrapper) -> Result<Vec<T>, String> {
+        if let NodeKind::List(lst) = &val.node {
+            lst.iter().map(T::from_miful).collect()
+
+        } else {
+            Err(expected("list", val))
+        }
+    }
+}
+
+macro_rules! tuple_conversion {
+    ($len:expr; $($t:ident $i:tt),*) => {
+        impl<$($t: IntoMiful),*> IntoMiful for ($($t,)*) {
+            fn into_miful(self) -> NodeWrapper {
+                NodeWrapper::new_list(vec![$(self.$i.into_miful()),*], vec![], Span::default())
+            }
+        }
+
+        impl<$($t: FromMiful),*> FromMiful for ($($t,)*) {
+            fn from_miful(val: &NodeWrapper) -> Result<($($t,)*), String> {
+                match &val.node {
+                    NodeKind::List(lst) if lst.len() == $len => {
+                        Ok(($(
+                            match $t::from_miful(&lst[$i]) {
+                                Ok(v) => v,
+                                Err(e) => { return Err(e); },
+                            },
+                        )*))
+                    },
+
+                    _ => { Err(expected(&format!("list of {}", $len), val)) },
+                }
+            }
+        }
+    };
+}
+
+tuple_conversion!(1; A 0);
+tuple_conversion!(2; A 0, B 1);
+tuple_conversion!(3; A 0, B 1, C 2);
+tuple_conversion!(4; A 0, B 1, C 2, D 3);
+tuple_conversion!(5; A 0, B 1, C 2, D 3, E 4);
+tuple_conversion!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+// [NOTE] Sorted, so the same map always gives the same list.
+//
+impl<K: IntoMiful + Eq + Hash + Ord, V: IntoMiful> IntoMiful for HashMap<K, V> {
+    fn into_miful(self) -> NodeWrapper {
+        let sorted: BTreeMap<K, V> = self.into_iter().collect();
+
+        sorted.into_miful()
+    }
+}
+
+impl<K: FromMiful + Eq + Hash, V: FromMiful> FromMiful for HashMap<K, V> {
+    fn from_miful(val: &NodeWrapper) -> Result<HashMap<K, V>, String> {
+        let pairs: Result<Vec<(K, V)>, String> = FromMiful::from_miful(val);
+
+        match pairs {
+            Ok(pairs) => { Ok(pairs.into_iter().collect()) },
+            Err(e) => { Err(e) },
+        }
+    }
+}
+
+impl<K: IntoMiful + Ord, V: IntoMiful> IntoMiful for BTreeMap<K, V> {
+    fn into_miful(self) -> NodeWrapper {
+        let pairs = self.into_iter().map(IntoMiful::into_miful).collect();
+
+        NodeWrapper::new_list(pairs, vec![], Span::default())
+    }
+}
+
+impl<K: FromMiful + Ord, V: FromMiful> FromMiful for BTreeMap<K, V> {
+    fn from_miful(val: &NodeWrapper) -> Result<BTreeMap<K, V>, String> {
+        let pairs: Result<Vec<(K, V)>, String> = FromMiful::from_miful(val);
+
+        match pairs {
+            Ok(pairs) => { Ok(pairs.into_iter().collect()) },
+            Err(e) => { Err(e) },
+        }
+    }
+}
+
+//
+// [END] Collections
+
+
+// [AREA] Objects
+//
+
+fn is_object(val: &NodeWrapper, name: &str) -> bool {
+    if let NodeKind::List(st) = &val.node {
+        if st.len() == 3 {
+            if let (NodeKind::Symbol(v), NodeKind::Word(s) | NodeKind::Symbol(s), NodeKind::List(_)) = (&st[0].node, &st[1].node, &st[2].node) {
+                return v == "_obj" && s == name;
+            }
+        }
+    }
+
+    false
+}
+
+// [NOTE] Whether `val` is (obj nil), the result of `print`, `define` and the like.
+// The REPL and `miful eval` don't print it.
+//
+pub fn is_nil(val: &NodeWrapper) -> bool {
+    is_object(val, "nil")
+}
+
+// [NOTE] Builds an object of structure `name`, fields go in declaration order:
+//
+//    Object::new("item").field(self.name).field(self.damage).build()
+//
+pub struct Object {
+    name: String,
+    fields: Vec<NodeWrapper>,
+}
+
+impl Object {
+    pub fn new(name: &str) -> Object {
+        Object {
+            name: name.to_owned(),
+            fields: vec![],
+        }
+    }
+
+    pub fn field<T: IntoMiful>(self, value: T) -> Object {
+        let mut obj = self;
+
+        obj.fields.push(value.into_miful());
+
+        obj
+    }
+
+    pub fn build(self) -> NodeWrapper {
+        let span = Span::default();
+
+        let obj_sym = NodeWrapper::new_symbol("_obj".to_owned(), span);
+        let obj_type = NodeWrapper::new_word(self.name, span);
+
+        let obj_val = NodeWrapper::new_list(self.fields, vec![], span);
+
+        NodeWrapper::new_list(vec![obj_sym, obj_type, obj_val], vec![], span)
+    }
+}
+
+// [NOTE] Reads the fields of an object of structure `name` in declaration order:
+//
+//    let mut fields = ObjectFields::read(val, "item")?;
+//
+//    Ok(Item { name: fields.field()?, damage: fields.field()? })
+//
+pub struct ObjectFields {
+    name: String,
+    fields: Vec<NodeWrapper>,
+
+    index: usize,
+}
+
+impl ObjectFields {
+    pub fn read(val: &NodeWrapper, name: &str) -> Result<ObjectFields, String> {
+        if !is_object(val, name) {
+            return Err(expected(&format!("(obj {})", name), val));
+        }
+
+        if let NodeKind::List(st) = &val.node {
+            if let NodeKind::List(fields) = &st[2].node {
+                return Ok(ObjectFields {
+                    name: name.to_owned(),
+                    fields: fields.to_vec(),
+
+                    index: 0,
+                });
+            }
+        }
+
+        unreachable!();// [UNREACHABLE] Checked by `is_object`.
+    }
+
+    // [NOTE] The next field, converted to `T`.
+    //
+    pub fn field<T: FromMiful>(&mut self) -> Result<T, String> {
+        if self.index >= self.fields.len() {
+            return Err(format!("Object ` {} ` has only {} fields!", self.name, self.fields.len()));
+        }
+
+        self.index += 1;
+
+        T::from_miful(&self.fields[self.index - 1])
+    }
+}
+
+//
+// [END] Objects
+
+
+#[cfg(test)]
+mod tests {
+    use super::{ IntoMiful, FromMiful, Object, ObjectFields, is_nil };
+    use parsing::ast::NodeWrapper;
+    use parsing::span::Span;
+
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+
+    fn round_trip<T: IntoMiful + FromMiful + Clone + PartialEq + Debug>(v: T) {
+        assert_eq!(T::from_miful(&v.clone().into_miful()), Ok(v));
+    }
+
+    #[test]
+    fn values_round_trip() {
+        round_trip(true);
+        round_trip(false);
+        round_trip(-7i8);
+        round_trip(u32::MAX);
+        round_trip(Some(3i64));
+        round_trip(None::<i64>);
+        round_trip(Some(Some("x".to_owned())));
+        round_trip(vec![1i64, 2, 3]);
+        round_trip(Vec::<String>::new());
+        round_trip((1i64, "a".to_owned(), true));
+        round_trip(vec![(1i32, Some(2.5f64)), (3, None)]);
+
+        assert_eq!(true.into_miful().to_string(), "true");
+        assert_eq!(vec![Some(1i64), None].into_miful().to_string(), "(1 (_obj nil ()))");
+        assert_eq!((1i64, "a").into_miful().to_string(), "(1 \"a\")");
+    }
+
+    #[test]
+    fn mismatches_are_errors() {
+        assert!(bool::from_miful(&"true".into_miful()).is_err());
+        assert!(<(i64, i64)>::from_miful(&vec![1i64].into_miful()).is_err());
+        assert!(Vec::<i64>::from_miful(&vec![(1i64,)].into_miful()).is_err());
+        assert!(Option::<i64>::from_miful(&"x".into_miful()).is_err());
+    }
+
+    #[test]
+    fn narrowing_is_checked() {
+        assert_eq!(i8::from_miful(&300.into_miful()), Err("Int ` 300 ` is out of range of ` i8 `!".to_owned()));
+        assert!(u8::from_miful(&(-1).into_miful()).is_err());
+        assert!(i32::from_miful(&i64::MAX.into_miful()).is_err());
+
+        assert_eq!(u64::from_miful(&i64::MAX.into_miful()), Ok(i64::MAX as u64));
+        assert_eq!(usize::from_miful(&12.into_miful()), Ok(12));
+        assert!(u64::from_miful(&(-1).into_miful()).is_err());
+        assert!(usize::from_miful(&i64::MIN.into_miful()).is_err());
+    }
+
+    #[test]
+    fn maps_convert_in_key_order() {
+        let map: HashMap<i64, &str> = (0 .. 20).map(|i| (19 - i, "x")).collect();
+        let expected: Vec<String> = (0 .. 20).map(|i| format!("({} \"x\")", i)).collect();
+
+        assert_eq!(map.into_miful().to_string(), format!("({})", expected.join(" ")));
+    }
+
+    #[test]
+    fn objects_named_by_symbols() {
+        let span = Span::default();
+
+        let obj = NodeWrapper::new_list(vec![
+            NodeWrapper::new_symbol("_obj".to_owned(), span),
+            NodeWrapper::new_symbol("+vec".to_owned(), span),
+            NodeWrapper::new_list(vec![1.into_miful(), 2.into_miful()], vec![], span),
+        ], vec![], span);
+
+        let mut fields = ObjectFields::read(&obj, "+vec").unwrap();
+
+        assert_eq!(fields.field::<i64>(), Ok(1));
+        assert_eq!(fields.field::<i64>(), Ok(2));
+        assert!(fields.field::<i64>().is_err());
+
+        let item = Object::new("item").field("sword").build();
+
+        assert_eq!(ObjectFields::read(&item, "item").unwrap().field::<String>(), Ok("sword".to_owned()));
+    }
+
+    #[test]
+    fn nil_is_recognised() {
+        assert!(is_nil(&().into_miful()));
+        assert!(!is_nil(&Object::new("item").build()));
+        assert!(!is_nil(&"nil".into_miful()));
+    }
+}
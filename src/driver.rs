@@ -1,8 +1,18 @@
 use parsing;
 use parsing::token as tok;
-use parsing::ast::{ NodeWrapper, NodeKind, MifulType };
-use parsing::utils::{ MifulError, segment_text, input };
-
+use parsing::lexer::Lexer;
+use parsing::ast::{ NodeWrapper, NodeKind, MifulType, Closure, escape_str };
+use parsing::span::Span;
+use parsing::utils::{ MifulError, Error, Frame, FrameKind, codes, segment_text };
+use console::Console;
+use modules::{ Modules, Module, Functions };
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::io;
+use std::fs;
+use std::path::PathBuf;
+use std::cmp::{ self, Ordering };
 use std::collections::{ HashSet, HashMap };
 
 
@@ -23,11 +33,28 @@ use std::collections::{ HashSet, HashMap };
         [if [> [: age] 18] {display [: adult-content]} {display [: denial]}]
         ```
 
+* < (value:1) (value:2)
+    > returns sym(true) if (value:1) is less than (value:2), sym(false) otherwise
+    > NOTE: Ints and floats are compared with their own kind, words and symbols
+    lexicographically, lists element by element.
+    > NOTE: `>`, `<=` and `>=` work the same way.
+
+* and (value:1) (value:2) ...
+    > returns sym(true) if all values are sym(true), stops evaluating at the first sym(false)
+
+* or (value:1) (value:2) ...
+    > returns sym(true) if any value is sym(true), stops evaluating at the first sym(true)
+
+* not (value)
+    > negates sym(true) or sym(false)
+    > NOTE: Any other value throws runtime error (applies to `and` and `or`, too).
+
 * + (int:1) (int:2)
     > adds (int:1) and (int:2)
 
 * + (float:1) (float:2)
     > adds (float:1) and (float:2)
+    > NOTE: An int with a float throws runtime error (applies to `-`, `*`, `/` and `%`, too).
 
 * + (word:1) (word:2)
     > concatenates (word:1) and (word:2)
@@ -46,50 +73,190 @@ use std::collections::{ HashSet, HashMap };
 
 * / (int:1) (int:2)
     > divides (int:1) and (int:2) and rounds the result down
+    > NOTE: Division by zero throws runtime error (applies to `%`, too).
 
 * / (float:1) (float:2)
     > divides (float:1) and (float:2)
 
 * % (int:1) (int:2)
     > performs integer division of (int:1) and (int:2) and returns the remainder
+    > NOTE: The remainder has the sign of (int:2).
 
 * % (float:1) (float:2)
     > performs integer division of (float:1) and (float:2) and returns the remainder
 
 * floor (float)
-    > rounds (float) towards negative infinity
+    > rounds (float) towards negative infinity and returns it as (int)
 
 * ceil (float)
-    > rounds (float) towards infinity
+    > rounds (float) towards infinity and returns it as (int)
 
 * round (float)
-    > rounds (float) to the nearest integer
+    > rounds (float) to the nearest integer and returns it as (int)
+    > NOTE: Halfway cases are rounded away from zero.
 
 * let (word) (value) {quote}
     > binds (value) to (word), every call to [: (word)] inside {quote} will result into (value)
     > NOTE: Similar behaviour to an unquoted block {? ... ?}
     > NOTE: Value can be read multiple times.
+    > NOTE: The binding shadows outer bindings of the same name.
+
+* let* (list<list<word, value>>) {quote}
+    > binds every (value) to its (word) in order, then runs {quote}
+    > NOTE: Each value is evaluated with the preceding bindings already visible.
+    > NOTE: A malformed binding list throws runtime error.
+
+* lambda (list<list<word, (type)>>) {quote}
+    > returns {quote} taking arguments specified in (list<...>)
+    > NOTE: Like every quote, it sees the bindings from where it was written,
+    not from where it gets called.
+
+* apply {quote} (list)
+    > runs {quote} with (list) as arguments
+    > NOTE: Arguments of a quote not created by `lambda` are appended to its invoke.
+
+* call {quote} (value:1) (value:2) ...
+    > same as `apply` with the values as a list
 
 * define (word) (list<list<word, (type)>>) {quote}
     > creates a function binding for (word) with arguments specified in (list<...>),
     associated with {quote}
     > NOTE: This binding is valid after this definition (independent of scope).
     > NOTE: Redefining (shadowing) a function is not prohibited.
+    > NOTE: A call picks the most specific overload matching the arguments: a simple
+    type beats a union, which beats `any`. An overload that some arguments would match
+    as well as an existing one, with neither more specific, throws runtime error.
     > NOTE: Argument definition: 2-tuple - 1st element is name, 2nd is type (see ${Type structure})
+    > NOTE: The body sees the bindings and local functions from where it was defined.
 
 * undefine (word)
     > removes a function binding for (word)
     > NOTE: If (word) doesn't have a binding, throws runtime error.
 
-* split_at (word) (int)
-    > returns a 2-tuple - 1st element is first (int) letters of (word), 2nd is the rest
+* undefine (word) (list<type>)
+    > removes only the overload of (word) with the given argument types
+    > NOTE: If there is no such overload, throws runtime error.
+
+* defined? (word)
+    > returns sym(true) if (word) has a function binding, sym(false) otherwise
+    > NOTE: Built-in functions are not considered.
+
+* overloads (word)
+    > returns a list of symbols describing the signature of every overload of (word)
+
+* map (list) {quote}
+    > returns (list) with {quote} applied to every element (see `apply`)
+
+* filter (list) {quote}
+    > returns the elements of (list) for which {quote} returns sym(true)
+
+* fold (list) (value) {quote}
+    > applies {quote} to the accumulator and every element of (list), from left to right,
+    starting with (value) as the accumulator, and returns the final accumulator
+
+* zip (list:1) (list:2)
+    > returns a list of 2-tuples of elements at the same index, as long as the shorter list
+
+* range (int:1) (int:2)
+    > returns the list of ints from (int:1) up to, but not including, (int:2)
+    > NOTE: A list longer than 10 000 000 ints throws runtime error.
+
+* nth (list) (int)
+    > returns the element of (list) at index (int), counting from 0
+    > NOTE: Runtime error at negative index or index overflow
+
+* take (list) (int)
+    > returns the first (int) elements of (list)
+    > NOTE: Ignores index overflow
+    > NOTE: Runtime error at negative index (applies to `drop`, too)
+
+* drop (list) (int)
+    > returns (list) without the first (int) elements
+
+* concat (list<list>)
+    > joins all the lists in (list<list>) into one
+
+* concat (list<str>)
+    > joins all the strings in (list<str>) into one
+    > NOTE: The empty list is taken for a list of lists, so it returns the empty list,
+    not the empty string.
+
+* sort (list)
+    > returns (list) in ascending order, as compared by `<`
+
+* contains (list) (value)
+    > returns sym(true) if an element of (list) equals (value), sym(false) otherwise
+
+* split_at (str) (int)
+    > returns a 2-tuple - 1st element is first (int) letters of (str), 2nd is the rest
     > NOTE: Ignores index overflow
     > NOTE: Runtime error at negative index
 
-* struct (list<>)
+* split (str:1) (str:2)
+    > returns the list of parts of (str:1) separated by (str:2)
+    > NOTE: Empty (str:2) splits (str:1) into letters.
+
+* substring (str) (int:1) (int:2)
+    > returns letters of (str) from index (int:1) up to, but not including, (int:2)
+    > NOTE: Ignores index overflow
+
+* to-upper (str)
+    > returns (str) in upper case (`to-lower` works the same way)
+
+* trim (str)
+    > returns (str) without leading and trailing whitespace
+
+* format (str) (list)
+    > returns (str) with every `{}` replaced by the next value of (list)
+    > NOTE: `{{` and `}}` stand for literal braces.
+    > NOTE: Strings, words and symbols are inserted as they are.
+
+* to-str (value)
+    > returns (value) written as a string
+
+* to-int (str)
+    > returns the int written in (str) (`to-float` works the same way)
+    > NOTE: (str) is read like a number in the source, e.g. `0x10` or `1_000`.
+    > NOTE: If (str) isn't a number, throws runtime error (so does a float for `to-int`).
+
+* struct (word) (list<list<word, (type)>>)
+    > declares a structure named (word) with fields specified in (list<...>)
+    > NOTE: Field definition is the same as argument definition of `define`.
+    > NOTE: Redeclaring a structure replaces it.
+
+* new (word) (list)
+    > creates an object of structure (word) with field values (list), in declaration order
+    > NOTE: Values are checked against the field types, mismatch throws runtime error.
 
 * @ (structure) (word)
-    > returns the associated function named (word) of (structure)
+    > returns the field (word) of (structure)
+    > NOTE: If there is no such field, returns the associated function named (word)
+    of (structure) as a quote, with (structure) supplied as the first argument.
+
+* update (structure) (word) (value)
+    > returns a copy of (structure) with field (word) set to (value)
+
+* args
+    > returns the arguments given to the script as (list<str>)
+    > NOTE: Set by the host with `Driver::set_args`, empty otherwise.
+
+* import (str) (word)
+    > runs the file (str) once and defines its exported functions as (word)/(name)
+    > NOTE: Without (word), the functions are qualified by the name of the file.
+    > NOTE: See `modules.rs` for how (str) is found.
+
+* require (str)
+    > same as `import`, but defines the functions under their own names
+
+* export (word:1) (word:2) ...
+    > makes the file export only the functions named (word:1) (word:2) ...
+
+
+## Strings
+
+    String literals are written in double quotes and support the escapes
+`\n`, `\t`, `\r`, `\0`, `\\`, `\"` and `\u{...}` (hexadecimal code point).
+Their type is `str`. Letters of a string are its graphemes.
 
 
 ## Type structure
@@ -99,6 +266,56 @@ They have this structure:
 
     ( (internal state constants), (public functions) )
 
+    Objects are stored as ( `_obj` (name) (field values) ). Functions whose first
+argument is of type (obj name) act as the public functions of the structure.
+
+
+## Unquotes
+
+    A value inside unquote {? ... ?} is evaluated when the quote containing it is created,
+with the bindings from where the quote is written, and its result takes the place
+of the unquote:
+    ```
+    [let x 5 {let q {+ 1 {? [: x] ?}} {call [: q]}}]
+    ```
+    ..creates {+ 1 5}.
+
+    An unquote belongs to the innermost quote around it, so unquotes of a quote nested
+inside another quote are resolved only when the inner quote gets created (when
+the outer quote runs). Unquotes inside the value of an unquote are just a part
+of that value. An unquote outside of any quote simply evaluates its value.
+
+
+## Console
+
+    `print`, `eprint`, `flush` and `input` use the console of the driver, which is
+the terminal unless the host supplies its own with `Driver::with_console`.
+
+* print (str)
+    > writes (str) to the output
+
+* eprint (str)
+    > writes (str) to the error output
+
+* flush
+    > writes out everything printed so far
+
+* input (str)
+    > prints (str), then reads a line and returns it as (str)
+    > NOTE: The end of input throws runtime error.
+
+
+## Host functions
+
+    A host can give scripts functions of its own with `Driver::register_fn`.
+They get called like user functions, with the same overload resolution, and
+can't be defined or undefined by the script, like built-in functions. An error
+returned from a host function becomes a runtime error at the invoke.
+
+    ```
+    driver.register_fn("give-item", vec![MifulType::Simple("word".to_owned())], |args| { ... });
+    ```
+
 
 ## Unquote vs Function Arguments
 
@@ -108,11 +325,25 @@ as `let` bindings are.
 */
 
 
+// [NOTE] Function of the host, called with the evaluated arguments. The error
+// message becomes a runtime error at the invoke.
+//
+pub type HostFn = Rc<dyn Fn(&[NodeWrapper]) -> Result<NodeWrapper, String>>;
+
+// [NOTE] The longest list `range` builds, so a wrong bound throws an error
+// instead of taking all the memory.
+//
+const MAX_RANGE: i128 = 10_000_000;
+
 pub struct Driver<'a> {
     input: &'a str,
 
-    owned_text: Vec<String>,
-    keep_ws: bool,
+    owned_text: Rc<Vec<String>>,
+
+    // [NOTE] Every source read so far, by the file of its spans minus one. Errors
+    // are rendered against the source of the node being evaluated.
+    //
+    sources: Rc<RefCell<Vec<Rc<Vec<String>>>>>,
 
     index: usize,
     ast: Vec<NodeWrapper>,
@@ -126,131 +357,404 @@ pub struct Driver<'a> {
     // ..or list:
     //    (tuple (..types..)), (obj word)
     //
-    // [NOTE] Argument types allow no ambiguity.
+    // [NOTE] Argument types allow no ambiguity: of two overloads some value matches,
+    // one must be more specific (see `more_specific`), which `define` checks.
     //
     functions: HashMap<(String, Vec<MifulType>), (Vec<String>, NodeWrapper)>,
+
+    // [NOTE] Functions defined below the top level. These are captured by quotes,
+    // the rest of `functions` is looked up when a quote gets called.
+    //
+    locals: HashMap<(String, Vec<MifulType>), (Vec<String>, NodeWrapper)>,
+    top_level: bool,
+
+    // [NOTE] Field names and types of every declared structure, in declaration order.
+    //
+    structs: HashMap<String, Vec<(String, MifulType)>>,
+
+    // [NOTE] Functions registered by the host, with the same keys as `functions`.
+    //
+    host_functions: HashMap<(String, Vec<MifulType>), HostFn>,
+
+    console: Rc<Console>,
+
+    // [NOTE] Arguments given to the script by the host, returned by `args`.
+    //
+    args: Rc<Vec<String>>,
+
+    // [NOTE] File of the source, imports are resolved relative to it.
+    //
+    path: Option<PathBuf>,
+    modules: Rc<RefCell<Modules>>,
+
+    // [NOTE] Names given to `export`, `None` exports everything.
+    //
+    exports: Option<Vec<String>>,
 }
 
 
 impl<'a> Driver<'a> {
     pub fn new(input: &'a str) -> Driver<'a> {
+        Driver::with_console(input, Console::std())
+    }
+
+    // [NOTE] Creates a driver whose `print`, `eprint` and `input` use `console`
+    // instead of the terminal.
+    //
+    pub fn with_console(input: &'a str, console: Console) -> Driver<'a> {
         Driver {
             input,
 
-            owned_text: vec![],
-            keep_ws: false,
+            owned_text: Rc::new(vec![]),
+            sources: Rc::new(RefCell::new(vec![])),
 
             index: 0,
             ast: vec![],
 
-            scope: map!{
-                String::from("space") => NodeWrapper::new_symbol(" ".to_owned(), 0, (0, 0)),
-                String::from("tab") => NodeWrapper::new_symbol("\t".to_owned(), 0, (0, 0)),
-                String::from("newline") => NodeWrapper::new_symbol("\n".to_owned(), 0, (0, 0)),
-                String::from("carriage_ret") => NodeWrapper::new_symbol("\r".to_owned(), 0, (0, 0)),
-                String::from("l_bracket") => NodeWrapper::new_symbol("[".to_owned(), 0, (0, 0)),
-                String::from("r_bracket") => NodeWrapper::new_symbol("]".to_owned(), 0, (0, 0)),
-                String::from("l_brace") => NodeWrapper::new_symbol("{".to_owned(), 0, (0, 0)),
-                String::from("r_brace") => NodeWrapper::new_symbol("}".to_owned(), 0, (0, 0)),
-                String::from("l_paren") => NodeWrapper::new_symbol("(".to_owned(), 0, (0, 0)),
-                String::from("r_paren") => NodeWrapper::new_symbol(")".to_owned(), 0, (0, 0)),
-            },
+            scope: Driver::constants(),
 
             functions: map!{},
+            structs: map!{},
+            host_functions: map!{},
+
+            locals: map!{},
+            top_level: true,
+
+            console: Rc::new(console),
+            args: Rc::new(vec![]),
+
+            path: None,
+            modules: Rc::new(RefCell::new(Modules::new())),
+            exports: None,
         }
     }
 
-    fn over_input_line(input: &'a str) -> Driver<'a> {
-        Driver {
-            input,
+    // [NOTE] Makes `f` callable from the script as `name` with parameters of `signature`.
+    // Registering the same signature again replaces the function.
+    //
+    pub fn register_fn<F>(&mut self, name: &str, signature: Vec<MifulType>, f: F) -> Result<(), MifulError>
+        where F: Fn(&[NodeWrapper]) -> Result<NodeWrapper, String> + 'static {
 
-            owned_text: vec![],
-            keep_ws: true,
+        if Driver::builtin_functions().contains(name) {
+            return Err(MifulError::runtime_error(&format!("Cannot override built-in function ` {} `!", name), &self.owned_text, Span::default())
+                .with_code(codes::BUILTIN_FUNCTION));
+        }// [ERR] Built-in override
 
-            index: 0,
-            ast: vec![],
+        if let Err(e) = self.check_overload(name, &signature, Span::default()) {
+            return Err(e);
+        }
 
-            scope: map!{},
-            functions: map!{},
+        self.host_functions.insert((name.to_owned(), signature), Rc::new(f));
+
+        Ok(())
+    }
+
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = Rc::new(args);
+    }
+
+    // [NOTE] Sets the file of the source, to resolve its imports relative to.
+    //
+    // [NOTE] The file counts as being imported, so importing it back is a cycle.
+    //
+    pub fn set_path(&mut self, path: PathBuf) {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+        let _ = self.modules.borrow_mut().start(&canonical);
+
+        self.path = Some(path);
+    }
+
+    // [NOTE] Adds a directory where imports are looked up when they aren't found
+    // next to the importing file.
+    //
+    pub fn add_search_path(&mut self, dir: PathBuf) {
+        self.modules.borrow_mut().add_search_path(dir);
+    }
+
+    // [NOTE] Forgets every function and structure defined by the script. The console,
+    // host functions and arguments stay.
+    //
+    pub fn reset(&mut self) {
+        self.scope = Driver::constants();
+
+        self.functions.clear();
+        self.locals.clear();
+        self.structs.clear();
+
+        self.ast.clear();
+        self.index = 0;
+    }
+
+    pub fn console(&self) -> &Console {
+        &self.console
+    }
+
+    // [NOTE] Signatures of the functions defined by the script and the host, sorted.
+    //
+    pub fn signatures(&self) -> Vec<String> {
+        let mut sigs: Vec<String> = self.functions.keys()
+            .chain(self.host_functions.keys())
+            .map(|(name, types)| self.format_signature(name, types))
+            .collect();
+
+        sigs.sort();
+
+        sigs
+    }
+
+    // [NOTE] The most specific type `val` has, as written in signatures.
+    //
+    pub fn type_of(&self, val: &NodeWrapper) -> MifulType {
+        match &val.node {
+            NodeKind::Float(_) => { MifulType::Simple("float".to_owned()) },
+            NodeKind::Int(_) => { MifulType::Simple("int".to_owned()) },
+
+            NodeKind::Word(_) => { MifulType::Simple("word".to_owned()) },
+            NodeKind::Symbol(_) => { MifulType::Simple("symbol".to_owned()) },
+            NodeKind::Str(_) => { MifulType::Simple("str".to_owned()) },
+
+            NodeKind::List(lst) => {
+                match self.get_obj_type(val) {
+                    Ok(name) => { MifulType::Object(name) },
+                    Err(_) if lst.is_empty() => { MifulType::Simple("list".to_owned()) },
+                    Err(_) => { MifulType::Tuple(lst.iter().map(|v| self.type_of(v)).collect()) },
+                }
+            },
+
+            _ => { MifulType::Simple("quote".to_owned()) },
         }
     }
 
-    fn over(owned_text: Vec<String>, ast: Vec<NodeWrapper>, scope: HashMap<String, NodeWrapper>,
+    // [NOTE] Evaluates `ast` with the given scope and functions, sharing the structures,
+    // host functions, console and modules with `self`.
+    //
+    fn over(&self, ast: Vec<NodeWrapper>, scope: HashMap<String, NodeWrapper>,
         functions: HashMap<(String, Vec<MifulType>), (Vec<String>, NodeWrapper)>) -> Driver<'a> {
 
             Driver {
                 input: "",
 
-                owned_text,
-                keep_ws: false,
+                owned_text: self.source_of(&ast),
+                sources: self.sources.clone(),
 
                 index: 0,
                 ast,
 
                 scope,
                 functions,
+                structs: self.structs.clone(),
+                host_functions: self.host_functions.clone(),
+
+                locals: map!{},
+                top_level: false,
+
+                console: self.console.clone(),
+                args: self.args.clone(),
+
+                path: self.path.clone(),
+                modules: self.modules.clone(),
+                exports: None,
             }
     }
 
-    pub fn process(&mut self) -> Result<Vec<NodeWrapper>, MifulError> {
+    // [NOTE] Evaluates `ast` with the given scope, sharing everything else with `self`.
+    //
+    fn sub_driver(&self, ast: Vec<NodeWrapper>, scope: HashMap<String, NodeWrapper>) -> Driver<'a> {
+        let mut driver = self.over(ast, scope, self.functions.clone());
+
+        driver.locals = self.locals.clone();
+
+        driver
+    }
+
+    // [NOTE] Evaluates `ast` in the environment captured by `closure`, with `bindings` on top.
+    // Without a closure, this is the same as `sub_driver` over the current scope.
+    //
+    fn closure_driver(&self, ast: Vec<NodeWrapper>, closure: &Option<Rc<Closure>>, bindings: HashMap<String, NodeWrapper>) -> Driver<'a> {
+        match closure {
+            Some(c) => {
+                let mut loc_scope = c.scope.clone();
+                let mut loc_functions = self.functions.clone();
+
+                loc_scope.extend(bindings);
+
+                for key in self.locals.keys() {
+                    loc_functions.remove(key);
+                }
+
+                loc_functions.extend(c.functions.clone());
+
+                let mut driver = self.over(ast, loc_scope, loc_functions);
+
+                driver.locals = c.functions.clone();
+
+                driver
+            },
+
+            None => {
+                let mut loc_scope = self.scope.clone();
+
+                loc_scope.extend(bindings);
+
+                self.sub_driver(ast, loc_scope)
+            },
+        }
+    }
+
+    // [NOTE] The source `ast` comes from, the current one if unknown.
+    //
+    fn source_of(&self, ast: &[NodeWrapper]) -> Rc<Vec<String>> {
+        let file = ast.first().map_or(0, |node| node.span.file);
+
+        match self.sources.borrow().get(file.wrapping_sub(1)) {
+            Some(source) => { source.clone() },
+            None => { self.owned_text.clone() },
+        }
+    }
+
+    // [NOTE] Every call reads a new source, spans of its tokens get a new file.
+    //
+    fn lex(&mut self, source: &str) -> (Vec<tok::Token>, Vec<MifulError>) {
         let symbols = Driver::symbols();
-        let segmented_text = segment_text(self.input);
+        let segmented_text = segment_text(source);
 
-        self.owned_text = segmented_text.iter().cloned().map(ToOwned::to_owned).collect();
+        self.owned_text = Rc::new(segmented_text.iter().cloned().map(ToOwned::to_owned).collect());
 
-        if self.keep_ws {
-            let lexer = parsing::lexer::Lexer::new_ws_preserving(segmented_text, symbols);
-            let mut tokens: Vec<tok::Token> = lexer.collect();
+        let file = {
+            let mut sources = self.sources.borrow_mut();
 
-            tokens.pop();// [NOTE] Drop last newline.
+            sources.push(self.owned_text.clone());
+            sources.len()
+        };
 
-            let mut nodes = vec![];
+        let mut lexer = Lexer::new(segmented_text, symbols);
+        let mut tokens: Vec<tok::Token> = lexer.by_ref().collect();
 
-            for t in tokens {
-                let result: Result<Vec<_>, _> = parsing::parser::Parser::new(vec![t]).collect();
+        for t in &mut tokens {
+            t.span.file = file;
+        }
 
-                match result {
-                    Ok(lst) => {
-                        nodes.push(lst[0].clone());
-                    },
+        (tokens, lexer.errors().clone())
+    }
 
-                    Err(e) => {
-                        let mut new_e = e;
+    // [NOTE] Parses the source without stopping at the first error, returning
+    // the partial tree (with error nodes in place of broken structures)
+    // together with every lexing and parsing error.
+    //
+    pub fn diagnose(&mut self) -> (Vec<NodeWrapper>, Vec<MifulError>) {
+        let input = self.input;
+        let (tokens, mut errors) = self.lex(input);
+        let (ast, parse_errors) = parsing::parser::Parser::parse_all(tokens);
 
-                        new_e.add_layer_top("..while interpreting input line");
-                        new_e.supply_source(&self.owned_text);
+        for e in parse_errors {
+            let mut new_e = e;
 
-                        return Err(new_e);
-                    },
-                }
-            }
+            new_e.supply_source(&self.owned_text);
 
-            Ok(nodes)
+            errors.push(new_e);
+        }
 
-        } else {
-            let lexer = parsing::lexer::Lexer::new(segmented_text, symbols);
-            let tokens: Vec<tok::Token> = lexer.collect();
+        // [NOTE] Lexer errors come first otherwise.
+        //
+        errors.sort_by_key(|e| e.get_span().start);
 
-            let parser = parsing::parser::Parser::new(tokens);
-            let result: Result<Vec<_>, _> = parser.collect();
+        self.ast = ast.clone();
 
-            match result {
-                Ok(ast) => {
-                    self.ast = ast.clone();
+        (ast, errors)
+    }
 
-                    Ok(ast)
-                },
+    pub fn process(&mut self) -> Result<Vec<NodeWrapper>, MifulError> {
+        let input = self.input;
 
-                Err(e) => {
-                    let mut new_e = e;
+        self.feed(input)
+    }
+
+    // [NOTE] Parses `source` in place of the rest of the input, keeping the functions
+    // and structures defined so far. Iterating the driver then evaluates `source`.
+    //
+    pub fn feed(&mut self, source: &str) -> Result<Vec<NodeWrapper>, MifulError> {
+        let (tokens, lex_errors) = self.lex(source);
 
-                    new_e.add_layer_top("..while interpreting the source");
-                    new_e.supply_source(&self.owned_text);
+        if let Some(e) = lex_errors.first() {
+            let mut new_e = e.clone();
 
-                    Err(new_e)
-                },
+            new_e.add_frame(Frame::new(FrameKind::Parse, "while interpreting the source", Span::default()));
+
+            return Err(new_e);
+        }
+
+        let parser = parsing::parser::Parser::new(tokens);
+        let result: Result<Vec<_>, _> = parser.collect();
+
+        match result {
+            Ok(ast) => {
+                self.ast = ast.clone();
+                self.index = 0;
+
+                Ok(ast)
+            },
+
+            Err(e) => {
+                let mut new_e = e;
+
+                new_e.add_frame(Frame::new(FrameKind::Parse, "while interpreting the source", Span::default()));
+                new_e.supply_source(&self.owned_text);
+
+                Err(new_e)
+            },
+        }
+    }
+
+    // [NOTE] Checks the source without running it: reports every syntax error
+    // like `diagnose`, then validates the signatures of every `define` and
+    // `struct` written with a literal list.
+    //
+    pub fn check(&mut self) -> Vec<MifulError> {
+        let (ast, mut errors) = self.diagnose();
+
+        if errors.is_empty() {
+            for node in &ast {
+                self.check_signatures(node, &mut errors);
             }
         }
+
+        errors
+    }
+
+    fn check_signatures(&self, node: &NodeWrapper, errors: &mut Vec<MifulError>) {
+        match &node.node {
+            NodeKind::Invoke{ target, with } | NodeKind::Quote{ target, with } => {
+                if (target == "define" || target == "struct") && with.len() >= 2 {
+                    if let (NodeKind::Word(name), NodeKind::List(raw_signature)) = (&with[0].node, &with[1].node) {
+                        if let Err(e) = self.parse_signature(raw_signature) {
+                            let mut new_e = e;
+                            let what = if target == "define" { "function" } else { "structure" };
+
+                            new_e.add_frame(Frame::named(FrameKind::Define, name, &format!("while checking {} {}", what, name), node.span));
+
+                            errors.push(new_e);
+                        }
+                    }
+                }
+
+                for v in with {
+                    self.check_signatures(v, errors);
+                }
+            },
+
+            NodeKind::List(lst) => {
+                for v in lst {
+                    self.check_signatures(v, errors);
+                }
+            },
+
+            _ => {},
+        }
+
+        for hook in &node.hooks {
+            self.check_signatures(hook, errors);
+        }
     }
 
     // [AREA] Constant Utils
@@ -258,8 +762,23 @@ impl<'a> Driver<'a> {
     // [NOTE] Because of Rust's "Fuck you, you can't have a const set"
     //
 
+    fn constants() -> HashMap<String, NodeWrapper> {
+        map!{
+            String::from("space") => NodeWrapper::new_symbol(" ".to_owned(), Span::default()),
+            String::from("tab") => NodeWrapper::new_symbol("\t".to_owned(), Span::default()),
+            String::from("newline") => NodeWrapper::new_symbol("\n".to_owned(), Span::default()),
+            String::from("carriage_ret") => NodeWrapper::new_symbol("\r".to_owned(), Span::default()),
+            String::from("l_bracket") => NodeWrapper::new_symbol("[".to_owned(), Span::default()),
+            String::from("r_bracket") => NodeWrapper::new_symbol("]".to_owned(), Span::default()),
+            String::from("l_brace") => NodeWrapper::new_symbol("{".to_owned(), Span::default()),
+            String::from("r_brace") => NodeWrapper::new_symbol("}".to_owned(), Span::default()),
+            String::from("l_paren") => NodeWrapper::new_symbol("(".to_owned(), Span::default()),
+            String::from("r_paren") => NodeWrapper::new_symbol(")".to_owned(), Span::default()),
+        }
+    }
+
     #[inline]
-    fn symbols<'b>() -> HashSet<&'b str> {
+    pub(crate) fn symbols<'b>() -> HashSet<&'b str> {
         //
         // [NOTE] `?` and `@` are required to be symbols.
 
@@ -268,16 +787,28 @@ impl<'a> Driver<'a> {
 
     #[inline]
     fn builtin_functions<'b>() -> HashSet<&'b str> {
-        set!["print", "input", "mk-sym", ":", "return", "define", "obj-append", "length", "head",
-        "tail", "reverse", "=", "+", "-", "*", "if"]
+        set!["print", "eprint", "flush", "input", "args", "import", "require", "export", "mk-sym", ":", "return", "define", "undefine", "defined?", "overloads", "struct", "new", "@", "update", "obj-append", "length", "head",
+        "tail", "reverse", "map", "filter", "fold", "zip", "range", "nth", "take", "drop", "concat", "sort", "contains", "split", "substring", "split_at", "to-upper", "to-lower", "trim", "format", "to-str", "to-int", "to-float", "=", "<", ">", "<=", ">=", "and", "or", "not", "let", "let*", "lambda", "apply", "call", "+", "-", "*", "/", "%", "floor", "ceil", "round", "if"]
+    }
+
+    // [NOTE] Built-in and host functions can't be defined or undefined by scripts.
+    //
+    fn is_protected(&self, name: &str) -> bool {
+        Driver::builtin_functions().contains(name) || self.is_host_function(name)
+    }
+
+    fn is_host_function(&self, name: &str) -> bool {
+        self.host_functions.keys().any(|(f_name, _)| f_name == name)
     }
 
     //
     // [END] Constant Utils
 
-    // [TODO] Nested hooks?
+    // [NOTE] Replaces the hooks of a quote being created with the values of their
+    // expressions, evaluated here. Hooks of inner quotes are left for when
+    // those quotes get created themselves.
     //
-    fn resolve_hooks(&self, tree: NodeWrapper, hooks: &Vec<NodeWrapper>) -> NodeWrapper {
+    fn resolve_hooks(&self, tree: NodeWrapper) -> Result<NodeWrapper, MifulError> {
         let kind = tree.node.clone();
 
         match kind {
@@ -285,42 +816,57 @@ impl<'a> Driver<'a> {
                 let mut new_lst = vec![];
 
                 for val in lst {
-                    let new_val = self.resolve_hooks(val, hooks);
-
-                    new_lst.push(new_val);
+                    match self.resolve_hooks(val) {
+                        Ok(new_val) => { new_lst.push(new_val); },
+                        Err(e) => { return Err(e); },
+                    }
                 }
 
-                NodeWrapper::new_list(new_lst, vec![], tree.index, tree.position)
+                Ok(NodeWrapper::new_list(new_lst, vec![], tree.span))
             },
 
             NodeKind::Invoke{ target, with } => {
                 let mut new_with = vec![];
 
                 for arg in with {
-                    let new_arg = self.resolve_hooks(arg, hooks);
-
-                    new_with.push(new_arg);
+                    match self.resolve_hooks(arg) {
+                        Ok(new_arg) => { new_with.push(new_arg); },
+                        Err(e) => { return Err(e); },
+                    }
                 }
 
-                NodeWrapper::new_invoke(target, new_with, vec![], tree.index, tree.position)
+                Ok(NodeWrapper::new_invoke(target, new_with, vec![], tree.span))
             },
 
-            NodeKind::LambdaHook(idx) => {
-                hooks[idx].clone()
+            NodeKind::LambdaHook(_) => {
+                self.eval_hook(&tree)
             },
 
             _ => {
-                tree
+                Ok(tree)
             },
         }
     }
 
-    fn current(&self) -> Option<NodeWrapper> {
-        if self.index < self.ast.len() {
-            Some(self.ast[self.index].clone())
+    fn eval_hook(&self, hook: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let inner_driver = self.sub_driver(hook.hooks.clone(), self.scope.clone());
+        let result: Result<Vec<_>, _> = inner_driver.collect();
 
-        } else {
-            None
+        match result {
+            Ok(ret) => {
+                match ret.last() {
+                    Some(v) => { Ok(v.clone()) },
+                    None => { Ok(NodeWrapper::new_list(vec![], vec![], hook.span)) },
+                }
+            },
+
+            Err(e) => {
+                let mut new_e = e;
+
+                new_e.add_frame(Frame::new(FrameKind::Evaluate, "while evaluating unquote", hook.span));
+
+                Err(new_e)
+            },
         }
     }
 
@@ -329,27 +875,65 @@ impl<'a> Driver<'a> {
     //
 
     fn invalid_param_count(&self, exp_count: usize, got_count: usize, n: NodeWrapper) -> MifulError {
-        MifulError::runtime_error(&format!("Expected {} parameters; got {}!", exp_count, got_count), &self.owned_text, n.index, n.position)
+        MifulError::runtime_error(&format!("Expected {} parameters; got {}!", exp_count, got_count), &self.owned_text, n.span)
+            .with_code(codes::PARAM_COUNT)
     }
 
-    fn param_eval(&self, e: MifulError) -> MifulError {
-        let mut new_e = e;
+    // [NOTE] For builtins taking from `min_count` up to `max_count` parameters.
+    //
+    fn invalid_param_range(&self, min_count: usize, max_count: usize, got_count: usize, n: NodeWrapper) -> MifulError {
+        if min_count == max_count {
+            return self.invalid_param_count(min_count, got_count, n);
+        }
 
-        new_e.add_layer_top("..while evaluating invoke parameters");
+        let joint = if max_count == min_count + 1 { "or" } else { "to" };
 
-        new_e
+        MifulError::runtime_error(&format!("Expected {} {} {} parameters; got {}!", min_count, joint, max_count, got_count), &self.owned_text, n.span)
+            .with_code(codes::PARAM_COUNT)
     }
 
-    fn param_type(&self, exp_t: &str, idx: usize, pos: (usize, usize)) -> MifulError {
-        MifulError::runtime_error(&format!("Invalid parameter type, expecting ` {} `!", exp_t), &self.owned_text, idx, pos)
+    // [NOTE] `index` counts from 0, the frame from 1.
+    //
+    fn param_eval(&self, e: MifulError, name: &str, index: usize, span: Span) -> MifulError {
+        let mut new_e = e;
+
+        new_e.add_frame(Frame::named(FrameKind::Argument(index + 1), name, &format!("while evaluating argument {} of {}", index + 1, name), span));
+
+        new_e
     }
 
-    fn type_signature(&self, val_node: &NodeWrapper) -> MifulError {
-        MifulError::runtime_error("Invalid type signature!", &self.owned_text, val_node.index, val_node.position)
+    fn param_type(&self, exp_t: &str, span: Span) -> MifulError {
+        MifulError::runtime_error(&format!("Invalid parameter type, expecting ` {} `!", exp_t), &self.owned_text, span)
+            .with_code(codes::PARAM_TYPE)
     }
 
+    // [NOTE] Blames the first operand if no overload takes it, the second otherwise.
     //
-    // [END] Error Utils
+    fn operand_type(&self, exp_t: &str, a_ok: bool, a: &NodeWrapper, b: &NodeWrapper) -> MifulError {
+        if a_ok {
+            self.param_type(exp_t, b.span)
+
+        } else {
+            self.param_type(exp_t, a.span)
+        }
+    }
+
+    fn is_number(&self, val: &NodeWrapper) -> bool {
+        matches!(val.node, NodeKind::Int(_) | NodeKind::Float(_))
+    }
+
+    fn type_signature(&self, val_node: &NodeWrapper) -> MifulError {
+        MifulError::runtime_error("Invalid type signature!", &self.owned_text, val_node.span)
+            .with_code(codes::TYPE_SIGNATURE)
+    }
+
+    fn division_by_zero(&self, divisor: &NodeWrapper) -> MifulError {
+        MifulError::runtime_error("Division by zero!", &self.owned_text, divisor.span)
+            .with_code(codes::DIVISION_BY_ZERO)
+    }
+
+    //
+    // [END] Error Utils
 
 
     // [AREA] Type Utils
@@ -370,6 +954,7 @@ impl<'a> Driver<'a> {
 
                         NodeKind::Word(_) => { s == "word" },
                         NodeKind::Symbol(_) => { s == "symbol" },
+                        NodeKind::Str(_) => { s == "str" },
 
                         NodeKind::List(_) => { s == "list" },
 
@@ -383,7 +968,7 @@ impl<'a> Driver<'a> {
             },
 
             MifulType::Object(class_name) => {
-                self.check_obj_type(&val, class_name)
+                self.check_obj_type(val, class_name) && self.check_struct_shape(val_node)
             },
 
             MifulType::Tuple(types) => {
@@ -445,31 +1030,43 @@ impl<'a> Driver<'a> {
         false
     }
 
-    fn make_object(&self, t: String, lst: Vec<NodeWrapper>, index: usize, position: (usize, usize)) -> NodeWrapper {
-        let obj_sym = NodeWrapper::new_symbol("_obj".to_owned(), index, position);
-        let obj_type = NodeWrapper::new_word(t, index, position);
-
-        let mut hooks = vec![];
+    fn make_object(&self, t: String, lst: Vec<NodeWrapper>, span: Span) -> NodeWrapper {
+        let obj_sym = NodeWrapper::new_symbol("_obj".to_owned(), span);
+        let obj_type = NodeWrapper::new_word(t, span);
 
-        for t in &lst {
-            hooks.extend(t.hooks.clone());
-        }
-
-        let obj_val = NodeWrapper::new_list(lst, hooks.clone(), index, position);
+        let obj_val = NodeWrapper::new_list(lst, vec![], span);
 
-        NodeWrapper::new_list(vec![obj_sym, obj_type, obj_val], hooks, index, position)
+        NodeWrapper::new_list(vec![obj_sym, obj_type, obj_val], vec![], span)
     }
 
     fn make_nil(&self) -> NodeWrapper {
-        self.make_object("nil".to_owned(), vec![], 0, (0, 0))
+        self.make_object("nil".to_owned(), vec![], Span::default())
     }
 
     fn make_true(&self) -> NodeWrapper {
-        NodeWrapper::new_symbol("true".to_owned(), 0, (0, 0))
+        NodeWrapper::new_symbol("true".to_owned(), Span::default())
     }
 
     fn make_false(&self) -> NodeWrapper {
-        NodeWrapper::new_symbol("false".to_owned(), 0, (0, 0))
+        NodeWrapper::new_symbol("false".to_owned(), Span::default())
+    }
+
+    fn make_bool(&self, b: bool) -> NodeWrapper {
+        if b {
+            self.make_true()
+
+        } else {
+            self.make_false()
+        }
+    }
+
+    fn is_true(&self, val: &NodeWrapper) -> Result<bool, MifulError> {
+        match &val.node {
+            NodeKind::Symbol(s) if s == "true" => { Ok(true) },
+            NodeKind::Symbol(s) if s == "false" => { Ok(false) },
+
+            _ => { Err(self.param_type("(true | false)", val.span)) },
+        }// [ERR] Parameter type
     }
 
     fn get_obj_val(&self, obj: &NodeWrapper) -> Result<Vec<NodeWrapper>, MifulError> {
@@ -484,15 +1081,15 @@ impl<'a> Driver<'a> {
                     }// [UNREACHABLE]
 
                 } else {
-                    Err(MifulError::runtime_error("Invalid object structure!", &self.owned_text, obj.index, obj.position))
+                    Err(MifulError::runtime_error("Invalid object structure!", &self.owned_text, obj.span).with_code(codes::INVALID_OBJECT))
                 }// [ERR] Invalid object structure
 
             } else {
-                Err(MifulError::runtime_error("Object structure has invalid length!", &self.owned_text, obj.index, obj.position))
+                Err(MifulError::runtime_error("Object structure has invalid length!", &self.owned_text, obj.span).with_code(codes::INVALID_OBJECT))
             }// [Err] Invalid object length
 
         } else {
-            Err(MifulError::runtime_error("Object node is of invalid kind!", &self.owned_text, obj.index, obj.position))
+            Err(MifulError::runtime_error("Object node is of invalid kind!", &self.owned_text, obj.span).with_code(codes::INVALID_OBJECT))
         }// [Err] Invalid object kind
     }
 
@@ -508,15 +1105,15 @@ impl<'a> Driver<'a> {
                     }// [UNREACHABLE]
 
                 } else {
-                    Err(MifulError::runtime_error("Invalid object structure!", &self.owned_text, obj.index, obj.position))
+                    Err(MifulError::runtime_error("Invalid object structure!", &self.owned_text, obj.span).with_code(codes::INVALID_OBJECT))
                 }// [ERR] Invalid object structure
 
             } else {
-                Err(MifulError::runtime_error("Object structure has invalid length!", &self.owned_text, obj.index, obj.position))
+                Err(MifulError::runtime_error("Object structure has invalid length!", &self.owned_text, obj.span).with_code(codes::INVALID_OBJECT))
             }// [Err] Invalid object length
 
         } else {
-            Err(MifulError::runtime_error("Object node is of invalid kind!", &self.owned_text, obj.index, obj.position))
+            Err(MifulError::runtime_error("Object node is of invalid kind!", &self.owned_text, obj.span).with_code(codes::INVALID_OBJECT))
         }// [Err] Invalid object kind
     }
 
@@ -534,13 +1131,13 @@ impl<'a> Driver<'a> {
                                 .chain(lst.iter().cloned())
                                 .collect();
 
-                        Ok(self.make_object(obj_name, obj_val, obj.index, obj.position))
+                        Ok(self.make_object(obj_name, obj_val, obj.span))
                     },
 
                     Err(e) => {
                         let mut new_e = e;
 
-                        new_e.add_layer_top("..while processing object structure");
+                        new_e.add_frame(Frame::new(FrameKind::Other, "while processing object structure", obj.span));
 
                         Err(new_e)
                     },
@@ -550,7 +1147,7 @@ impl<'a> Driver<'a> {
             Err(e) => {
                 let mut new_e = e;
 
-                new_e.add_layer_top("..while processing object name");
+                new_e.add_frame(Frame::new(FrameKind::Other, "while processing object name", obj.span));
 
                 Err(new_e)
             },
@@ -565,8 +1162,8 @@ impl<'a> Driver<'a> {
             match node.node.clone() {
                 NodeKind::Word(t_name) | NodeKind::Symbol(t_name) => {
                     if t_name == "|" {
-                        if types.len() == 0 {
-                            return Err(MifulError::runtime_error("Invalid type union syntax!", &self.owned_text, node.index, node.position));
+                        if types.is_empty() {
+                            return Err(MifulError::runtime_error("Invalid type union syntax!", &self.owned_text, node.span).with_code(codes::TYPE_SIGNATURE));
                         }// [ERR] Union syntax
 
                         next_union = true;
@@ -584,7 +1181,7 @@ impl<'a> Driver<'a> {
                             match t_name.as_ref() {
                                 "tuple" => {
                                     if let NodeKind::List(t_list) = &lst[1].node {
-                                        let result = self.list_to_types(&t_list);
+                                        let result = self.list_to_types(t_list);
 
                                         match result {
                                             Ok(inner_types) => {
@@ -594,7 +1191,7 @@ impl<'a> Driver<'a> {
                                             Err(e) => {
                                                 let mut new_e = e;
 
-                                                new_e.add_layer_top("..while processing compound type");
+                                                new_e.add_frame(Frame::new(FrameKind::Other, "while processing compound type", node.span));
 
                                                 return Err(new_e);
                                             }// [ERR] Processing compound type
@@ -607,7 +1204,7 @@ impl<'a> Driver<'a> {
 
                                 "list" => {
                                     if let NodeKind::List(t_list) = &lst[1].node {
-                                        let result = self.list_to_types(&t_list);
+                                        let result = self.list_to_types(t_list);
 
                                         match result {
                                             Ok(inner_types) => {
@@ -617,7 +1214,7 @@ impl<'a> Driver<'a> {
                                             Err(e) => {
                                                 let mut new_e = e;
 
-                                                new_e.add_layer_top("..while processing compound type");
+                                                new_e.add_frame(Frame::new(FrameKind::Other, "while processing compound type", node.span));
 
                                                 return Err(new_e);
                                             }// [ERR] Processing compound type
@@ -641,7 +1238,7 @@ impl<'a> Driver<'a> {
                             }
 
                         } else {
-                            return Err(self.type_signature(&node));
+                            return Err(self.type_signature(node));
                         }// [ERR] Type signature
 
                     } else {
@@ -653,7 +1250,7 @@ impl<'a> Driver<'a> {
                                     types.push(inner_ts[0].clone())
 
                                 } else {
-                                    return Err(self.type_signature(&node));
+                                    return Err(self.type_signature(node));
                                 }// [ERR] Type signature
                             },
 
@@ -664,7 +1261,7 @@ impl<'a> Driver<'a> {
                     }// [ERR] Type signature
                 },
 
-                _ => { return Err(self.type_signature(&node)); },
+                _ => { return Err(self.type_signature(node)); },
             }
 
             if next_union {
@@ -693,834 +1290,3802 @@ impl<'a> Driver<'a> {
     // [END] Type Utils
 
 
-    // [AREA] Function Utils
+    // [AREA] Structure Utils
     //
 
-    fn print_fn(&self, val: NodeWrapper) -> Result<NodeWrapper, MifulError> {
-        let kind = &val.node;
+    fn declare_struct(&mut self, name: &str, raw_fields: &Vec<NodeWrapper>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        match self.parse_signature(raw_fields) {
+            Ok((names, types)) => {
+                for (i, field) in names.iter().enumerate() {
+                    if names[.. i].contains(field) {
+                        return Err(MifulError::runtime_error(&format!("Duplicate field ` {} `!", field), &self.owned_text, raw_fields[i].span).with_code(codes::DUPLICATE_FIELD));
+                    }// [ERR] Duplicate field
+                }
 
-        if let NodeKind::Word(s) | NodeKind::Symbol(s) = kind {
-            print!("{}", s);
+                self.structs.insert(name.to_owned(), names.into_iter().zip(types).collect());
 
-            Ok(self.make_nil())
+                Ok(self.make_nil())
+            },
 
-        } else if self.check_obj_type(kind, "string") {
-            let obj_struct = self.get_obj_val(&val).unwrap();
+            Err(e) => {
+                let mut new_e = e;
 
-            for v in obj_struct {
-                match self.print_fn(v) {//[TODO]
-                    Ok(_) => {},
+                new_e.add_frame(Frame::named(FrameKind::Define, name, &format!("while declaring structure {}", name), n.span));
 
-                    Err(e) => {
-                        return Err(self.param_eval(e));
-                    },
-                }
-            }
+                Err(new_e)
+            },
+        }
+    }
 
-            Ok(self.make_nil())
+    fn get_struct(&self, name: &str, n: &NodeWrapper) -> Result<&Vec<(String, MifulType)>, MifulError> {
+        if let Some(fields) = self.structs.get(name) {
+            Ok(fields)
 
         } else {
-            Err(self.param_type("(obj string)", val.index, val.position))
-        }// [ERR] Parameter type
+            Err(MifulError::runtime_error(&format!("Undeclared structure ` {} `!", name), &self.owned_text, n.span).with_code(codes::UNDECLARED_STRUCTURE))
+        }// [ERR] Undeclared structure
     }
 
-    fn args_compatible(&self, exp_args: &Vec<MifulType>, sup_args: &Vec<NodeWrapper>) -> bool {
-        if exp_args.len() == sup_args.len() {
-            for (val, t) in sup_args.iter().zip(exp_args.iter()) {
-                if !self.check_type(val, t) {
-                    return false;
-                }
+    // [NOTE] Objects of undeclared structures keep the old, unchecked behaviour.
+    //
+    fn check_struct_shape(&self, obj: &NodeWrapper) -> bool {
+        if let (Ok(name), Ok(values)) = (self.get_obj_type(obj), self.get_obj_val(obj)) {
+            if let Some(fields) = self.structs.get(&name) {
+                return fields.len() == values.len()
+                    && fields.iter()
+                        .zip(values.iter())
+                        .all(|((_, t), v)| self.check_type(v, t));
             }
+        }
 
-            true
+        true
+    }
 
-        } else {
-            false
+    fn construct_struct(&self, name: &str, values: &[NodeWrapper], n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let fields = match self.get_struct(name, n) {
+            Ok(fields) => fields,
+            Err(e) => { return Err(e); },
+        };
+
+        if fields.len() != values.len() {
+            return Err(MifulError::runtime_error(
+                &format!("Structure ` {} ` has {} fields; got {} values!", name, fields.len(), values.len()),
+                &self.owned_text,
+                n.span
+            ).with_code(codes::FIELD_COUNT));
+        }// [ERR] Field count
+
+        for ((field, t), v) in fields.iter().zip(values.iter()) {
+            if !self.check_type(v, t) {
+                return Err(MifulError::runtime_error(&format!("Invalid value of field ` {} `, expecting ` {} `!", field, t), &self.owned_text, v.span).with_code(codes::FIELD_TYPE));
+            }// [ERR] Field type
         }
+
+        Ok(self.make_object(name.to_owned(), values.to_vec(), n.span))
     }
 
-    fn choose_function(&self, name: &str, params: Vec<NodeWrapper>, n: &NodeWrapper) -> Result<(HashMap<String, NodeWrapper>, NodeWrapper), MifulError> {
-        let mut available = vec![];
+    fn field_index(&self, obj: &NodeWrapper, field: &str) -> Option<usize> {
+        match self.get_obj_type(obj) {
+            Ok(name) => {
+                self.structs.get(&name)
+                    .and_then(|fields| fields.iter().position(|(f, _)| f == field))
+            },
 
-        for ((f_name, exp_args), (arg_names, body_invoke)) in &self.functions {
-            if name == f_name {
-                if self.args_compatible(exp_args, &params) {
-                    let args = arg_names.iter()
-                        .cloned()
-                        .zip(params.iter().cloned())
-                        .collect();
+            Err(_) => { None },
+        }
+    }
 
-                    return Ok((args, body_invoke.clone()));
+    fn member_access(&self, obj: &NodeWrapper, member: &str, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        //
+        // [NOTE] Fields take precedence over functions. A function is a member of the object
+        // when its first parameter accepts the object; it is returned as a quote with
+        // the object already supplied.
+
+        let name = match self.get_obj_type(obj) {
+            Ok(name) => name,
+            Err(e) => { return Err(e); },
+        };
+
+        if let Some(i) = self.field_index(obj, member) {
+            match self.get_obj_val(obj) {
+                Ok(values) => { Ok(values[i].clone()) },
+                Err(e) => { Err(e) },
+            }
 
-                } else {
-                    let mut af = format!("{} :: ", f_name);
+        } else if self.functions.keys().any(|(f_name, types)| f_name == member && !types.is_empty() && self.check_type(obj, &types[0])) {
+            Ok(NodeWrapper::new_quote(member.to_owned(), vec![obj.clone()], vec![], n.span))
 
-                    for t in exp_args {
-                        af.push_str(&format!("{}, ", t));
-                    }
+        } else {
+            Err(MifulError::runtime_error(&format!("Structure ` {} ` has no member ` {} `!", name, member), &self.owned_text, n.span).with_code(codes::UNKNOWN_FIELD))
+        }// [ERR] Unknown member
+    }
 
-                    af.pop();
-                    af.pop();
+    fn update_struct(&self, obj: &NodeWrapper, field: &str, value: &NodeWrapper, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let name = match self.get_obj_type(obj) {
+            Ok(name) => name,
+            Err(e) => { return Err(e); },
+        };
 
-                    if exp_args.len() == 0 {
-                        af.pop();
-                        af.pop();
-                    }
+        let fields = match self.get_struct(&name, n) {
+            Ok(fields) => fields,
+            Err(e) => { return Err(e); },
+        };
 
-                    available.push(af);
-                }// Extending available functions
+        if let Some(i) = self.field_index(obj, field) {
+            if !self.check_type(value, &fields[i].1) {
+                return Err(MifulError::runtime_error(&format!("Invalid value of field ` {} `, expecting ` {} `!", field, fields[i].1), &self.owned_text, value.span).with_code(codes::FIELD_TYPE));
+            }// [ERR] Field type
+
+            match self.get_obj_val(obj) {
+                Ok(values) => {
+                    let mut new_values = values;
+
+                    new_values[i] = value.clone();
+
+                    Ok(self.make_object(name, new_values, n.span))
+                },
+
+                Err(e) => { Err(e) },
             }
-        }
 
-        // [TODO] Maybe print given parameter types?
-        //
-        Err(MifulError::runtime_error(
-            &format!("Did not find function ` {} ` with desired parameter types.\n\t[NOTE] Following are available:\n\t{}",
-                name,
-                available.join("\n\t")),
-            &self.owned_text,
-            n.index,
-            n.position
-        ))
+        } else {
+            Err(MifulError::runtime_error(&format!("Structure ` {} ` has no field ` {} `!", name, field), &self.owned_text, n.span).with_code(codes::UNKNOWN_FIELD))
+        }// [ERR] Unknown field
     }
 
-    fn call_function(&self, name: &str, params: Vec<NodeWrapper>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
-        let result = self.choose_function(name, params, n);
+    //
+    // [END] Structure Utils
 
-        match result {
-            Ok((args, body)) => {
-                let mut loc_scope = self.scope.clone();
 
-                loc_scope.extend(args);
+    // [AREA] List Utils
+    //
 
-                // [TODO] Local functions?
-                // (from the scope of the function declaration)
-                //
-                // [IDEA] Maybe insert the outer function body when processing quote.
-                //
-                let call_driver = Driver::over(self.owned_text.clone(), vec![body.clone()], loc_scope, self.functions.clone());
-                let call_result: Result<Vec<_>, _> = call_driver.collect();
+    fn expect_list(&self, val: &NodeWrapper) -> Result<Vec<NodeWrapper>, MifulError> {
+        if self.check_type(val, &MifulType::List(vec![MifulType::Simple("any".to_owned())])) {
+            if let NodeKind::List(lst) = &val.node {
+                return Ok(lst.to_vec());
+            }
+        }
 
-                match call_result {
-                    Ok(ret) => {
-                        return Ok(ret[0].clone());
-                    },
+        Err(self.param_type("(list (any))", val.span))
+    }
 
-                    Err(e) => {
-                        let mut new_e = e;
+    fn expect_index(&self, val: &NodeWrapper) -> Result<usize, MifulError> {
+        match &val.node {
+            NodeKind::Int(i) if *i >= 0 => { Ok(*i as usize) },
 
-                        new_e.add_layer_top(&format!("..while calling function {}", name));
+            NodeKind::Int(_) => {
+                Err(MifulError::runtime_error("Negative index!", &self.owned_text, val.span).with_code(codes::INDEX_OUT_OF_RANGE))
+            },// [ERR] Negative index
 
-                        return Err(new_e);
-                    },
-                }
-            },
+            _ => { Err(self.param_type("int", val.span)) },
+        }// [ERR] Parameter type
+    }
 
-            Err(e) => {
-                Err(e)
-            },
+    fn map_list(&self, lst: Vec<NodeWrapper>, quote: &NodeWrapper, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let mut mapped = vec![];
+
+        for val in lst {
+            match self.apply_quote(quote, vec![val], n) {
+                Ok(ret) => { mapped.push(ret); },
+
+                Err(e) => {
+                    let mut new_e = e;
+
+                    new_e.add_frame(Frame::named(FrameKind::Builtin, "map", "while mapping list", n.span));
+
+                    return Err(new_e);
+                },
+            }
         }
+
+        Ok(NodeWrapper::new_list(mapped, vec![], n.span))
     }
 
-    fn define_function(&mut self, name: &str, raw_signature: Vec<NodeWrapper>, body: NodeWrapper) -> Result<NodeWrapper, MifulError> {
-        //
-        // [NOTE] `body` is already converted from quote to invoke.
+    fn filter_list(&self, lst: Vec<NodeWrapper>, quote: &NodeWrapper, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let mut kept = vec![];
 
-        if !Driver::builtin_functions().contains(name) {
-            let mut names = vec![];
-            let mut raw_types = vec![];
+        for val in lst {
+            let result = self.apply_quote(quote, vec![val.clone()], n)
+                .and_then(|ret| self.is_true(&ret));
 
-            for raw_arg in raw_signature {
-                if let NodeKind::List(raw_pair) = &raw_arg.node {
-                    if raw_pair.len() == 2 {
-                        if let NodeKind::Word(name) | NodeKind::Symbol(name) = &raw_pair[0].node {
-                            names.push(name.to_owned());
-                            raw_types.push(raw_pair[1].clone());
+            match result {
+                Ok(true) => { kept.push(val); },
+                Ok(false) => {},
 
-                        } else {
-                            return Err(self.type_signature(&raw_pair[0]));
-                        }// [ERR] Type signature
+                Err(e) => {
+                    let mut new_e = e;
 
-                    } else {
-                        return Err(self.type_signature(&raw_arg));
-                    }// [ERR] Type signature
+                    new_e.add_frame(Frame::named(FrameKind::Builtin, "filter", "while filtering list", n.span));
 
-                } else {
-                    return Err(self.type_signature(&raw_arg));
-                }// [ERR] Type signature
+                    return Err(new_e);
+                },
             }
+        }
 
-            match self.list_to_types(&raw_types) {
-                Ok(types) => {
-                    // let result = self.inline_invokes(&body);
-                    //
-                    // match result {
-                    //     Ok(processed_body) => {
-                    //         self.functions.insert((name.to_owned(), types), (names, processed_body));
-                    //
-                    //         Ok(self.make_nil())
-                    //     },
-                    //
-                    //     Err(e) => {
-                    //         let mut new_e = e;
-                    //
-                    //         new_e.add_layer_top("..while defining function");
-                    //
-                    //         Err(new_e)
-                    //     }// [ERR] While defining function
-                    // }
+        Ok(NodeWrapper::new_list(kept, vec![], n.span))
+    }
 
-                    self.functions.insert((name.to_owned(), types), (names, body));
+    fn fold_list(&self, lst: Vec<NodeWrapper>, init: &NodeWrapper, quote: &NodeWrapper, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let mut acc = init.clone();
 
-                    Ok(self.make_nil())
-                },
+        for val in lst {
+            match self.apply_quote(quote, vec![acc, val], n) {
+                Ok(ret) => { acc = ret; },
 
                 Err(e) => {
                     let mut new_e = e;
 
-                    new_e.add_layer_top("..while defining function");
+                    new_e.add_frame(Frame::named(FrameKind::Builtin, "fold", "while folding list", n.span));
 
-                    Err(new_e)
-                }// [ERR] Function definition
+                    return Err(new_e);
+                },
             }
+        }
 
-        } else {
-            let current = self.current().unwrap();
-
-            return Err(MifulError::runtime_error("Cannot override built-in function!", &self.owned_text, current.index, current.position))
-        }// [ERR] Built-in override
+        Ok(acc)
     }
 
-    fn values_equal(&self, v1: &NodeWrapper, v2: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
-        let current = self.current().unwrap();
-
-        match (&v1.node, &v2.node) {
-            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
-                if i1 == i2 {
-                    Ok(self.make_true())
-
-                } else {
-                    Ok(self.make_false())
-                }
+    fn sort_list(&self, lst: Vec<NodeWrapper>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        match self.merge_sort(lst) {
+            Ok(sorted) => {
+                Ok(NodeWrapper::new_list(sorted, vec![], n.span))
             },
 
-            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
-                if f1 == f2 {
-                    Ok(self.make_true())
+            Err(e) => {
+                let mut new_e = e;
 
-                } else {
-                    Ok(self.make_false())
-                }
+                new_e.add_frame(Frame::named(FrameKind::Builtin, "sort", "while sorting list", n.span));
+
+                Err(new_e)
             },
+        }
+    }
 
-            (NodeKind::Word(w1), NodeKind::Word(w2)) => {
-                if w1 == w2 {
-                    Ok(self.make_true())
+    // [NOTE] `sort_by` needs a total order, which values of mixed types don't have.
+    // Merging by hand stops at the first pair that can't be compared.
+    //
+    fn merge_sort(&self, lst: Vec<NodeWrapper>) -> Result<Vec<NodeWrapper>, MifulError> {
+        if lst.len() < 2 {
+            return Ok(lst);
+        }
 
-                } else {
-                    Ok(self.make_false())
-                }
-            },
+        let mut left = lst;
+        let right = left.split_off(left.len() / 2);
 
-            (NodeKind::Symbol(s1), NodeKind::Symbol(s2)) => {
-                if s1 == s2 {
-                    Ok(self.make_true())
+        let (left, right) = match (self.merge_sort(left), self.merge_sort(right)) {
+            (Ok(left), Ok(right)) => { (left, right) },
 
-                } else {
-                    Ok(self.make_false())
-                }
+            (Err(e), _) | (_, Err(e)) => {
+                return Err(e);
             },
+        };
 
-            (NodeKind::List(l1), NodeKind::List(l2)) => {
-                if l1.len() == l2.len() {
-                    for (a, b) in l1.iter().zip(l2.iter()) {
-                        let result = self.values_equal(a, b);
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+        let mut left = left.into_iter().peekable();
+        let mut right = right.into_iter().peekable();
 
-                        match result {
-                            Ok(ret) => {
-                                if let NodeKind::Symbol(b) = ret.node {
-                                    if b == "false" {
-                                        return Ok(self.make_false());
-                                    }
+        while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+            match self.compare_values(a, b) {
+                Ok(Ordering::Greater) => { merged.extend(right.next()); },
+                Ok(_) => { merged.extend(left.next()); },
 
-                                } else {
-                                    unreachable!();
-                                }// [UNREACHABLE]
-                            },
+                Err(e) => {
+                    return Err(e);
+                },
+            }
+        }
 
-                            Err(e) => {
-                                let mut new_e = e;
+        merged.extend(left);
+        merged.extend(right);
 
-                                new_e.add_layer_top("..while checking list equality");
+        Ok(merged)
+    }
 
-                                return Err(new_e);
-                            }// [ERR] While checking list equality
-                        }
-                    }
+    //
+    // [END] List Utils
 
-                    Ok(self.make_true())
 
-                } else {
-                    Ok(self.make_false())
-                }
-            },
+    // [AREA] String Utils
+    //
+    // [NOTE] Indices into strings count graphemes, not bytes.
+    //
 
-            (NodeKind::LambdaHook(i1), NodeKind::LambdaHook(i2)) => {
-                if i1 == i2 {
-                    Ok(self.make_true())
+    fn expect_str(&self, val: &NodeWrapper) -> Result<String, MifulError> {
+        if let NodeKind::Str(s) = &val.node {
+            Ok(s.to_owned())
 
-                } else {
-                    Ok(self.make_false())
-                }
-            },
+        } else {
+            Err(self.param_type("str", val.span))
+        }// [ERR] Parameter type
+    }
 
-            (NodeKind::Invoke{ target: _, with: _ }, _) => { panic!("[values_equal] Unprocessed node!"); },
-            (_, NodeKind::Invoke{ target: _, with: _ }) => { panic!("[values_equal] Unprocessed node!"); },
+    fn make_str(&self, s: &str, n: &NodeWrapper) -> NodeWrapper {
+        NodeWrapper::new_str(s.to_owned(), n.span)
+    }
 
-            (NodeKind::Quote{ target: _, with: _ }, _) => { Err(MifulError::runtime_error("Can't check equality of quote!", &self.owned_text, current.index, current.position)) },
-            (_, NodeKind::Quote{ target: _, with: _ }) => { Err(MifulError::runtime_error("Can't check equality of quote!", &self.owned_text, current.index, current.position)) },
+    // [NOTE] Strings and words are written as they are, everything else as it's displayed.
+    //
+    fn to_text(&self, val: &NodeWrapper) -> String {
+        match &val.node {
+            NodeKind::Str(s) | NodeKind::Word(s) | NodeKind::Symbol(s) => { s.to_owned() },
 
-            _ => { Ok(self.make_false()) }
+            _ => { val.to_string() },
         }
     }
 
-    //
-    // [END] Function Utils
-}
+    fn split_str(&self, s: &str, sep: &str, n: &NodeWrapper) -> NodeWrapper {
+        let parts = if sep.is_empty() {
+            segment_text(s).iter().map(|g| self.make_str(g, n)).collect()
 
+        } else {
+            s.split(sep).map(|part| self.make_str(part, n)).collect()
+        };
 
-impl<'a> Iterator for Driver<'a> {
-    type Item = Result<NodeWrapper, MifulError>;
+        NodeWrapper::new_list(parts, vec![], n.span)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index >= self.ast.len() {
-            None
+    fn substring(&self, s: &str, from: usize, to: usize) -> String {
+        let graphemes = segment_text(s);
 
-        } else {
-            let n = self.ast[self.index].clone();
+        let to = cmp::min(to, graphemes.len());
+        let from = cmp::min(from, to);
 
-            let kind = n.node.clone();
-            let hooks = n.hooks.clone();
+        graphemes[from .. to].join("")
+    }
 
-            let own_text = self.owned_text.clone();
-            let loc_scope = self.scope.clone();
-            let loc_functions = self.functions.clone();
+    fn format_str(&self, template: &str, values: &[NodeWrapper], n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        //
+        // [NOTE] Every `{}` is replaced by the next value, `{{` and `}}` are literal braces.
 
-            self.index += 1;
+        let mut output = String::new();
+        let mut rest = values.iter();
 
-            match kind {
-                NodeKind::Word(_) | NodeKind::Symbol(_) | NodeKind::Int(_) | NodeKind::Float(_) => {
-                    Some(Ok(n))
-                },
+        let mut chars = template.chars().peekable();
 
-                // [TODO] Hooks
-                //
-                NodeKind::List(ref lst) => {
-                    let inner_driver = Driver::over(own_text, lst.to_vec(), loc_scope, loc_functions);
-                    let result = inner_driver.collect();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek().cloned()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
 
-                    match result {
-                        Ok(ret) => {
-                            Some(Ok(NodeWrapper::new_list(ret, vec![], n.index, n.position)))
-                        },
+                    output.push(c);
+                },
 
-                        Err(e) => {
-                            let mut new_e = e;
+                ('{', Some('}')) => {
+                    chars.next();
 
-                            new_e.add_layer_top("..while evaluating list elements");
+                    if let Some(val) = rest.next() {
+                        output.push_str(&self.to_text(val));
 
-                            Some(Err(new_e))
-                        },
-                    }
+                    } else {
+                        return Err(MifulError::runtime_error("Not enough values to format!", &self.owned_text, n.span).with_code(codes::FORMAT_VALUES));
+                    }// [ERR] Missing value
                 },
 
-                // [TODO] Hooks
-                //
-                NodeKind::Invoke{ target, with } => {
-                    let inner_driver = Driver::over(own_text, with.to_vec(), loc_scope, loc_functions);
-                    let result: Result<Vec<_>, _> = inner_driver.collect();
+                _ => {
+                    output.push(c);
+                },
+            }
+        }
+
+        if rest.next().is_some() {
+            return Err(MifulError::runtime_error("Too many values to format!", &self.owned_text, n.span).with_code(codes::FORMAT_VALUES));
+        }// [ERR] Extra value
+
+        Ok(self.make_str(&output, n))
+    }
+
+    //
+    // [END] String Utils
+
+
+    // [AREA] Function Utils
+    //
+
+    fn io_error(&self, e: io::Error, span: Span) -> MifulError {
+        MifulError::runtime_error(&format!("Console failed: {}!", e), &self.owned_text, span)
+            .with_code(codes::CONSOLE)
+    }
+
+    // [NOTE] Writes into the error output of the console if `error` is set.
+    //
+    fn print_fn(&self, val: NodeWrapper, error: bool) -> Result<NodeWrapper, MifulError> {
+        let kind = &val.node;
+
+        if let NodeKind::Word(s) | NodeKind::Symbol(s) | NodeKind::Str(s) = kind {
+            let result = if error { self.console.write_err(s) } else { self.console.write(s) };
+
+            match result {
+                Ok(_) => { Ok(self.make_nil()) },
+                Err(e) => { Err(self.io_error(e, val.span)) },
+            }// [ERR] Console
+
+        } else if self.check_obj_type(kind, "string") {
+            let obj_struct = self.get_obj_val(&val).unwrap();
+
+            for v in obj_struct {
+                match self.print_fn(v, error) {//[TODO]
+                    Ok(_) => {},
+
+                    Err(e) => {
+                        let name = if error { "eprint" } else { "print" };
+
+                        return Err(self.param_eval(e, name, 0, val.span));
+                    },
+                }
+            }
+
+            Ok(self.make_nil())
+
+        } else {
+            Err(self.param_type("(str | (obj string))", val.span))
+        }// [ERR] Parameter type
+    }
+
+    fn float_to_int(&self, f: f64, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        if f.is_finite() && f >= i64::MIN as f64 && f < i64::MAX as f64 {
+            Ok(NodeWrapper::new_int(f as i64, n.span))
+
+        } else {
+            Err(MifulError::runtime_error(&format!("Cannot convert ` {} ` to int!", f), &self.owned_text, n.span).with_code(codes::INVALID_CONVERSION))
+        }// [ERR] Integer range
+    }
+
+    fn args_compatible(&self, exp_args: &[MifulType], sup_args: &[NodeWrapper]) -> bool {
+        if exp_args.len() == sup_args.len() {
+            for (val, t) in sup_args.iter().zip(exp_args.iter()) {
+                if !self.check_type(val, t) {
+                    return false;
+                }
+            }
+
+            true
+
+        } else {
+            false
+        }
+    }
+
+    fn format_signature(&self, name: &str, types: &Vec<MifulType>) -> String {
+        let mut af = format!("{} :: ", name);
+
+        for t in types {
+            af.push_str(&format!("{}, ", t));
+        }
+
+        af.pop();
+        af.pop();
+
+        if types.is_empty() {
+            af.pop();
+            af.pop();
+        }
+
+        af
+    }
+
+    fn overloads_of(&self, name: &str) -> Vec<&Vec<MifulType>> {
+        let mut sigs: Vec<&Vec<MifulType>> = self.functions.keys()
+            .filter(|(f_name, _)| f_name == name)
+            .map(|(_, types)| types)
+            .collect();
+
+        sigs.sort_by_key(|types| self.format_signature(name, types));
+
+        sigs
+    }
+
+    fn undefine_function(&mut self, name: &str, signature: Option<Vec<MifulType>>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        if self.is_protected(name) {
+            return Err(MifulError::runtime_error("Cannot undefine built-in function!", &self.owned_text, n.span).with_code(codes::BUILTIN_FUNCTION));
+        }// [ERR] Built-in undefine
+
+        match signature {
+            Some(types) => {
+                self.locals.remove(&(name.to_owned(), types.clone()));
+
+                if self.functions.remove(&(name.to_owned(), types.clone())).is_some() {
+                    Ok(self.make_nil())
+
+                } else {
+                    Err(MifulError::runtime_error(
+                        &format!("Function ` {} ` is not defined!", self.format_signature(name, &types)),
+                        &self.owned_text,
+                        n.span
+                    ).with_code(codes::UNDEFINED_FUNCTION))
+                }// [ERR] Undefined overload
+            },
+
+            None => {
+                let count = self.functions.len();
+
+                self.functions.retain(|(f_name, _), _| f_name != name);
+                self.locals.retain(|(f_name, _), _| f_name != name);
+
+                if self.functions.len() < count {
+                    Ok(self.make_nil())
+
+                } else {
+                    Err(MifulError::runtime_error(&format!("Function ` {} ` is not defined!", name), &self.owned_text, n.span).with_code(codes::UNDEFINED_FUNCTION))
+                }// [ERR] Undefined function
+            },
+        }
+    }
+
+    fn choose_function(&self, name: &str, params: Vec<NodeWrapper>, n: &NodeWrapper) -> Result<(HashMap<String, NodeWrapper>, NodeWrapper), MifulError> {
+        let mut available = vec![];
+        let mut candidates = vec![];
+        let mut compatible = vec![];
+
+        for ((f_name, exp_args), (arg_names, body_invoke)) in &self.functions {
+            if name == f_name {
+                if self.args_compatible(exp_args, &params) {
+                    compatible.push((exp_args, (arg_names, body_invoke)));
+
+                } else {
+                    available.push(self.format_signature(f_name, exp_args));
+                    candidates.push(body_invoke.span);
+                }// Extending available functions
+            }
+        }
+
+        if compatible.is_empty() {
+            available.sort();
+
+            return Err(self.no_matching_function(name, available, candidates, n));
+        }
+
+        match self.most_specific(name, compatible, n) {
+            Ok((arg_names, body_invoke)) => {
+                let args = arg_names.iter()
+                    .cloned()
+                    .zip(params.iter().cloned())
+                    .collect();
+
+                Ok((args, body_invoke.clone()))
+            },
+
+            Err(e) => {
+                Err(e)
+            },
+        }
+    }
+
+    fn call_host_function(&self, name: &str, params: Vec<NodeWrapper>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let mut available = vec![];
+
+        let mut compatible = vec![];
+
+        for ((f_name, exp_args), f) in &self.host_functions {
+            if name == f_name {
+                if self.args_compatible(exp_args, &params) {
+                    compatible.push((exp_args, f));
+
+                } else {
+                    available.push(self.format_signature(f_name, exp_args));
+                }// Extending available functions
+            }
+        }
+
+        if compatible.is_empty() {
+            available.sort();
+
+            return Err(self.no_matching_function(name, available, vec![], n));
+        }
+
+        let f = match self.most_specific(name, compatible, n) {
+            Ok(f) => { f },
+            Err(e) => { return Err(e); },
+        };
+
+        match f(&params) {
+            Ok(ret) => {
+                let mut ret = ret;
+
+                ret.span = n.span;
+
+                Ok(ret)
+            },
+
+            Err(message) => {
+                Err(MifulError::runtime_error(&message, &self.owned_text, n.span).with_code(codes::HOST_FUNCTION))
+            },// [ERR] Host function
+        }
+    }
+
+    // [NOTE] How narrow a parameter type is: a simple type beats a union, which beats `any`.
+    // Structured types (objects, tuples and typed lists) beat the plain `list`.
+    //
+    fn specificity(t: &MifulType) -> u8 {
+        match t {
+            MifulType::Simple(s) if s == "any" => { 0 },
+            MifulType::AnyOf(_) => { 1 },
+            MifulType::Simple(_) => { 2 },
+
+            MifulType::Object(_) | MifulType::Tuple(_) | MifulType::List(_) => { 3 },
+        }
+    }
+
+    // [NOTE] Whether every parameter of `a` is at least as specific as that of `b`,
+    // and one of them more.
+    //
+    fn more_specific(a: &[MifulType], b: &[MifulType]) -> bool {
+        let pairs: Vec<(u8, u8)> = a.iter()
+            .zip(b.iter())
+            .map(|(t_a, t_b)| (Driver::specificity(t_a), Driver::specificity(t_b)))
+            .collect();
+
+        pairs.iter().all(|(s_a, s_b)| s_a >= s_b) && pairs.iter().any(|(s_a, s_b)| s_a > s_b)
+    }
+
+    // [NOTE] Whether some value has both types.
+    //
+    fn types_overlap(t1: &MifulType, t2: &MifulType) -> bool {
+        let obj_shape = |ts: &Vec<MifulType>| {
+            ts.len() == 3
+                && Driver::types_overlap(&ts[0], &MifulType::Simple("symbol".to_owned()))
+                && Driver::types_overlap(&ts[1], &MifulType::Simple("word".to_owned()))
+                && Driver::types_overlap(&ts[2], &MifulType::Simple("list".to_owned()))
+        };
+
+        match (t1, t2) {
+            (MifulType::Simple(s), _) | (_, MifulType::Simple(s)) if s == "any" => { true },
+
+            (MifulType::AnyOf(ts), t) | (t, MifulType::AnyOf(ts)) => {
+                ts.iter().any(|t_i| Driver::types_overlap(t_i, t))
+            },
+
+            (MifulType::Simple(s1), MifulType::Simple(s2)) => { s1 == s2 },
+            (MifulType::Simple(s), _) | (_, MifulType::Simple(s)) => { s == "list" },
+
+            (MifulType::Object(o1), MifulType::Object(o2)) => { o1 == o2 },
+            (MifulType::Object(_), MifulType::Tuple(ts)) | (MifulType::Tuple(ts), MifulType::Object(_)) => { obj_shape(ts) },
+
+            (MifulType::Object(_), MifulType::List(ts)) | (MifulType::List(ts), MifulType::Object(_)) => {
+                ["symbol", "word", "list"].iter()
+                    .all(|s| ts.iter().any(|t| Driver::types_overlap(t, &MifulType::Simple(s.to_string()))))
+            },
+
+            (MifulType::Tuple(ts1), MifulType::Tuple(ts2)) => {
+                ts1.len() == ts2.len() && ts1.iter().zip(ts2.iter()).all(|(a, b)| Driver::types_overlap(a, b))
+            },
+
+            (MifulType::Tuple(ts1), MifulType::List(ts2)) | (MifulType::List(ts2), MifulType::Tuple(ts1)) => {
+                ts1.iter().all(|a| ts2.iter().any(|b| Driver::types_overlap(a, b)))
+            },
+
+            // [NOTE] The empty list is both.
+            //
+            (MifulType::List(_), MifulType::List(_)) => { true },
+        }
+    }
+
+    // [NOTE] Whether some arguments would match both signatures equally well.
+    //
+    fn signatures_ambiguous(a: &[MifulType], b: &[MifulType]) -> bool {
+        a.len() == b.len()
+            && a.iter().zip(b.iter()).all(|(t_a, t_b)| Driver::types_overlap(t_a, t_b))
+            && !Driver::more_specific(a, b) && !Driver::more_specific(b, a)
+    }
+
+    // [NOTE] Picks the overload more specific than every other compatible one.
+    //
+    fn most_specific<T>(&self, name: &str, compatible: Vec<(&Vec<MifulType>, T)>, n: &NodeWrapper) -> Result<T, MifulError> {
+        let best: Vec<usize> = (0 .. compatible.len())
+            .filter(|&i| compatible.iter().all(|(other, _)| !Driver::more_specific(other, compatible[i].0)))
+            .collect();
+
+        if best.len() == 1 {
+            let mut compatible = compatible;
+
+            return Ok(compatible.swap_remove(best[0]).1);
+        }
+
+        let mut tied: Vec<String> = best.iter()
+            .map(|&i| self.format_signature(name, compatible[i].0))
+            .collect();
+
+        tied.sort();
+
+        Err(MifulError::runtime_error(
+            &format!("Call of ` {} ` is ambiguous.\n\t[NOTE] Following match equally well:\n\t{}", name, tied.join("\n\t")),
+            &self.owned_text,
+            n.span
+        ).with_code(codes::AMBIGUOUS_OVERLOAD))
+    }
+
+    // [NOTE] Every way of adding an overload (`define`, imports and host functions)
+    // goes through here. Fails if an overload of `name` other than `types`, by the
+    // script or the host, is ambiguous with it.
+    //
+    fn check_overload(&self, name: &str, types: &Vec<MifulType>, span: Span) -> Result<(), MifulError> {
+        let mut clashes: Vec<String> = self.functions.keys()
+            .chain(self.host_functions.keys())
+            .filter(|(f_name, f_types)| f_name == name && f_types != types && Driver::signatures_ambiguous(f_types, types))
+            .map(|(f_name, f_types)| self.format_signature(f_name, f_types))
+            .collect();
+
+        clashes.sort();
+
+        match clashes.first() {
+            Some(clash) => {
+                Err(MifulError::runtime_error(
+                    &format!("Overload ` {} ` is ambiguous with ` {} `!", self.format_signature(name, types), clash),
+                    &self.owned_text,
+                    span
+                ).with_code(codes::AMBIGUOUS_OVERLOAD))
+            },// [ERR] Ambiguous overload
+
+            None => { Ok(()) },
+        }
+    }
+
+    // [NOTE] `candidates` are the spans of user functions of the name.
+    //
+    fn no_matching_function(&self, name: &str, available: Vec<String>, candidates: Vec<Span>, n: &NodeWrapper) -> MifulError {
+        let mut candidates = candidates;
+
+        // [TODO] Maybe print given parameter types?
+        //
+        let mut e = MifulError::runtime_error(
+            &format!("Did not find function ` {} ` with desired parameter types.\n\t[NOTE] Following are available:\n\t{}",
+                name,
+                available.join("\n\t")),
+            &self.owned_text,
+            n.span
+        ).with_code(codes::NO_MATCHING_FUNCTION);
+
+        // [NOTE] Candidates from other sources can't be shown in this one.
+        //
+        candidates.retain(|span| span.file == n.span.file);
+        candidates.sort_by_key(|span| span.start);
+
+        for span in candidates {
+            e.add_label(span, "candidate defined here");
+        }
+
+        e
+    }
+
+    fn call_function(&self, name: &str, params: Vec<NodeWrapper>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        if self.is_host_function(name) {
+            return self.call_host_function(name, params, n);
+        }
+
+        let result = self.choose_function(name, params, n);
+
+        match result {
+            Ok((args, body)) => {
+                let mut call_driver = self.closure_driver(vec![body.clone()], &body.closure, args);
+
+                // [NOTE] Lets a local function see itself, so that it can recurse.
+                //
+                for (key, val) in &self.functions {
+                    if key.0 == name {
+                        call_driver.functions.insert(key.clone(), val.clone());
+
+                        if self.locals.contains_key(key) {
+                            call_driver.locals.insert(key.clone(), val.clone());
+                        }
+                    }
+                }
+
+                let call_result: Result<Vec<_>, _> = call_driver.collect();
+
+                match call_result {
+                    Ok(ret) => {
+                        Ok(ret[0].clone())
+                    },
+
+                    Err(e) => {
+                        let mut new_e = e;
+
+                        new_e.add_frame(Frame::named(FrameKind::Call, name, &format!("while calling function {}", name), n.span));
+
+                        Err(new_e)
+                    },
+                }
+            },
+
+            Err(e) => {
+                Err(e)
+            },
+        }
+    }
+
+    fn parse_signature(&self, raw_signature: &Vec<NodeWrapper>) -> Result<(Vec<String>, Vec<MifulType>), MifulError> {
+        let mut names = vec![];
+        let mut raw_types = vec![];
+
+        for raw_arg in raw_signature {
+            if let NodeKind::List(raw_pair) = &raw_arg.node {
+                if raw_pair.len() == 2 {
+                    if let NodeKind::Word(name) | NodeKind::Symbol(name) = &raw_pair[0].node {
+                        names.push(name.to_owned());
+                        raw_types.push(raw_pair[1].clone());
+
+                    } else {
+                        return Err(self.type_signature(&raw_pair[0]));
+                    }// [ERR] Type signature
+
+                } else {
+                    return Err(self.type_signature(raw_arg));
+                }// [ERR] Type signature
+
+            } else {
+                return Err(self.type_signature(raw_arg));
+            }// [ERR] Type signature
+        }
+
+        match self.list_to_types(&raw_types) {
+            Ok(types) => {
+                Ok((names, types))
+            },
+
+            Err(e) => {
+                Err(e)
+            },
+        }
+    }
+
+    fn define_function(&mut self, name: &str, raw_signature: Vec<NodeWrapper>, body: NodeWrapper, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        //
+        // [NOTE] `body` is already converted from quote to invoke.
+
+        if !self.is_protected(name) {
+            match self.parse_signature(&raw_signature) {
+                Ok((names, types)) => {
+                    // let result = self.inline_invokes(&body);
+                    //
+                    // match result {
+                    //     Ok(processed_body) => {
+                    //         self.functions.insert((name.to_owned(), types), (names, processed_body));
+                    //
+                    //         Ok(self.make_nil())
+                    //     },
+                    //
+                    //     Err(e) => {
+                    //         let mut new_e = e;
+                    //
+                    //         new_e.add_layer_top("..while defining function");
+                    //
+                    //         Err(new_e)
+                    //     }// [ERR] While defining function
+                    // }
+
+                    if let Err(e) = self.check_overload(name, &types, n.span) {
+                        return Err(e);
+                    }
+
+                    if !self.top_level {
+                        self.locals.insert((name.to_owned(), types.clone()), (names.clone(), body.clone()));
+                    }
+
+                    self.functions.insert((name.to_owned(), types), (names, body));
+
+                    Ok(self.make_nil())
+                },
+
+                Err(e) => {
+                    let mut new_e = e;
+
+                    new_e.add_frame(Frame::named(FrameKind::Define, name, &format!("while defining function {}", name), n.span));
+
+                    Err(new_e)
+                }// [ERR] Function definition
+            }
+
+        } else {
+            Err(MifulError::runtime_error("Cannot override built-in function!", &self.owned_text, n.span).with_code(codes::BUILTIN_FUNCTION))
+        }// [ERR] Built-in override
+    }
+
+    fn values_equal(&self, v1: &NodeWrapper, v2: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        match (&v1.node, &v2.node) {
+            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
+                Ok(self.make_bool(i1 == i2))
+            },
+
+            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
+                Ok(self.make_bool(f1 == f2))
+            },
+
+            (NodeKind::Word(w1), NodeKind::Word(w2)) => {
+                Ok(self.make_bool(w1 == w2))
+            },
+
+            (NodeKind::Str(s1), NodeKind::Str(s2)) => {
+                Ok(self.make_bool(s1 == s2))
+            },
+
+            (NodeKind::Symbol(s1), NodeKind::Symbol(s2)) => {
+                Ok(self.make_bool(s1 == s2))
+            },
+
+            (NodeKind::List(l1), NodeKind::List(l2)) if l1.len() == l2.len() => {
+                for (a, b) in l1.iter().zip(l2.iter()) {
+                    let result = self.values_equal(a, b);
+
+                    match result {
+                        Ok(ret) => {
+                            if let NodeKind::Symbol(b) = ret.node {
+                                if b == "false" {
+                                    return Ok(self.make_false());
+                                }
+
+                            } else {
+                                unreachable!();
+                            }// [UNREACHABLE]
+                        },
+
+                        Err(e) => {
+                            let mut new_e = e;
+
+                            new_e.add_frame(Frame::new(FrameKind::Other, "while checking list equality", v1.span));
+
+                            return Err(new_e);
+                        }// [ERR] While checking list equality
+                    }
+                }
+
+                Ok(self.make_true())
+            },
+
+            (NodeKind::LambdaHook(i1), NodeKind::LambdaHook(i2)) => {
+                Ok(self.make_bool(i1 == i2))
+            },
+
+            (NodeKind::Invoke{ target: _, with: _ }, _) => { panic!("[values_equal] Unprocessed node!"); },
+            (_, NodeKind::Invoke{ target: _, with: _ }) => { panic!("[values_equal] Unprocessed node!"); },
+
+            (NodeKind::Quote{ target: _, with: _ }, _) => { Err(MifulError::runtime_error("Can't check equality of quote!", &self.owned_text, v1.span).with_code(codes::INCOMPARABLE)) },
+            (_, NodeKind::Quote{ target: _, with: _ }) => { Err(MifulError::runtime_error("Can't check equality of quote!", &self.owned_text, v2.span).with_code(codes::INCOMPARABLE)) },
+
+            _ => { Ok(self.make_false()) }
+        }
+    }
+
+    // [NOTE] Ints and floats are compared only among themselves, words and symbols
+    // lexicographically, strings among themselves, and lists element-wise.
+    //
+    fn compare_values(&self, v1: &NodeWrapper, v2: &NodeWrapper) -> Result<Ordering, MifulError> {
+        match (&v1.node, &v2.node) {
+            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
+                Ok(i1.cmp(i2))
+            },
+
+            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
+                if let Some(ord) = f1.partial_cmp(f2) {
+                    Ok(ord)
+
+                } else {
+                    Err(MifulError::runtime_error("Cannot compare NaN!", &self.owned_text, v2.span).with_code(codes::INCOMPARABLE))
+                }// [ERR] NaN comparison
+            },
+
+            (NodeKind::Word(s1), NodeKind::Word(s2))
+            | (NodeKind::Word(s1), NodeKind::Symbol(s2))
+            | (NodeKind::Symbol(s1), NodeKind::Word(s2))
+            | (NodeKind::Symbol(s1), NodeKind::Symbol(s2))
+            | (NodeKind::Str(s1), NodeKind::Str(s2)) => {
+                Ok(s1.cmp(s2))
+            },
+
+            (NodeKind::List(l1), NodeKind::List(l2)) => {
+                for (a, b) in l1.iter().zip(l2.iter()) {
+                    match self.compare_values(a, b) {
+                        Ok(Ordering::Equal) => {},
+                        Ok(ord) => { return Ok(ord); },
+
+                        Err(e) => {
+                            let mut new_e = e;
+
+                            new_e.add_frame(Frame::new(FrameKind::Other, "while comparing list elements", v1.span));
+
+                            return Err(new_e);
+                        },
+                    }
+                }
+
+                Ok(l1.len().cmp(&l2.len()))
+            },
+
+            _ => {
+                Err(MifulError::runtime_error(
+                    &format!("Cannot compare ` {} ` with ` {} `!", self.type_of(v1), self.type_of(v2)),
+                    &self.owned_text,
+                    v2.span
+                ).with_code(codes::INCOMPARABLE))
+            },// [ERR] Incomparable values
+        }
+    }
+
+    fn eval_quote(&self, quote: &NodeWrapper, bindings: HashMap<String, NodeWrapper>) -> Result<NodeWrapper, MifulError> {
+        if let NodeKind::Quote{ target, with } = &quote.node {
+            let invoke = NodeWrapper::new_invoke(target.to_string(), with.to_vec(), quote.hooks.clone(), quote.span);
+
+            let inner_driver = self.closure_driver(vec![invoke], &quote.closure, bindings);
+            let result: Result<Vec<_>, _> = inner_driver.collect();
+
+            match result {
+                Ok(ret) => {
+                    Ok(ret[0].clone())
+                },
+
+                Err(e) => {
+                    let mut new_e = e;
+
+                    new_e.add_frame(Frame::new(FrameKind::Evaluate, "while evaluating quote", quote.span));
+
+                    Err(new_e)
+                },
+            }
+
+        } else {
+            Err(self.param_type("quote", quote.span))
+        }// [ERR] Parameter type
+    }
+
+    fn apply_quote(&self, quote: &NodeWrapper, args: Vec<NodeWrapper>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        //
+        // [NOTE] A `lambda` binds the arguments to its parameters, any other quote
+        // gets them appended to its invoke.
+
+        if let NodeKind::Quote{ target, with } = &quote.node {
+            let params = match &quote.closure {
+                Some(c) => c.params.clone(),
+                None => None,
+            };
+
+            match params {
+                Some(params) => {
+                    if params.len() != args.len() {
+                        return Err(self.invalid_param_count(params.len(), args.len(), n.clone()));
+                    }// [ERR] Parameter count
+
+                    let mut bindings = map!{};
+
+                    for ((name, t), arg) in params.into_iter().zip(args) {
+                        if !self.check_type(&arg, &t) {
+                            return Err(self.param_type(&t.to_string(), arg.span));
+                        }// [ERR] Parameter type
+
+                        bindings.insert(name, arg);
+                    }
+
+                    self.eval_quote(quote, bindings)
+                },
+
+                None => {
+                    let mut new_with = with.to_vec();
+
+                    new_with.extend(args);
+
+                    let mut applied = NodeWrapper::new_quote(target.to_owned(), new_with, quote.hooks.clone(), quote.span);
+
+                    applied.closure = quote.closure.clone();
+
+                    self.eval_quote(&applied, map!{})
+                },
+            }
+
+        } else {
+            Err(self.param_type("quote", quote.span))
+        }// [ERR] Parameter type
+    }
+
+    fn binding_name(&self, binding: &NodeWrapper) -> Result<String, MifulError> {
+        if let NodeKind::Word(name) | NodeKind::Symbol(name) = &binding.node {
+            Ok(name.to_owned())
+
+        } else {
+            Err(MifulError::runtime_error("Invalid binding name, expecting ` (word | symbol) `!", &self.owned_text, binding.span).with_code(codes::INVALID_BINDING))
+        }// [ERR] Binding name
+    }
+
+    fn let_star(&self, with: &[NodeWrapper], n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        //
+        // [NOTE] Bindings are evaluated in order, each one seeing the previous ones.
+        // A later binding shadows an earlier binding of the same name.
+        //
+        // [NOTE] The bindings and the body share one driver, so functions defined
+        // while binding are captured by the body.
+
+        if with.len() != 2 {
+            return Err(self.invalid_param_count(2, with.len(), n.clone()));
+        }// [ERR] Parameter count
+
+        let raw_bindings = &with[0];
+
+        let mut driver = self.sub_driver(vec![], self.scope.clone());
+        let mut bindings = map!{};
+
+        if let NodeKind::List(binding_lst) = &raw_bindings.node {
+            for binding in binding_lst {
+                if let NodeKind::List(pair) = &binding.node {
+                    if pair.len() != 2 {
+                        return Err(MifulError::runtime_error("Invalid binding, expecting ` (name value) `!", &self.owned_text, binding.span).with_code(codes::INVALID_BINDING));
+                    }// [ERR] Binding length
+
+                    let name = match self.binding_name(&pair[0]) {
+                        Ok(name) => name,
+                        Err(e) => { return Err(e); },
+                    };
+
+                    driver.ast.push(pair[1].clone());
+
+                    match driver.next() {
+                        Some(Ok(val)) => {
+                            driver.scope.insert(name.clone(), val.clone());
+                            bindings.insert(name, val);
+                        },
+
+                        Some(Err(e)) => {
+                            let mut new_e = e;
+
+                            new_e.add_frame(Frame::named(FrameKind::Bind, &name, &format!("while binding {}", name), binding.span));
+
+                            return Err(new_e);
+                        },
+
+                        None => {
+                            unreachable!();
+                        },// [UNREACHABLE]
+                    }
+
+                } else {
+                    return Err(MifulError::runtime_error("Invalid binding, expecting ` (name value) `!", &self.owned_text, binding.span).with_code(codes::INVALID_BINDING));
+                }// [ERR] Binding kind
+            }
+
+        } else {
+            return Err(self.param_type("(list *(list (word any)))", raw_bindings.span));
+        }// [ERR] Parameter type
+
+        driver.ast.push(with[1].clone());
+
+        match driver.next() {
+            Some(Ok(quote)) => {
+                self.eval_quote(&quote, bindings)
+            },
+
+            Some(Err(e)) => {
+                Err(self.param_eval(e, "let*", 1, with[1].span))
+            },
+
+            None => {
+                unreachable!();
+            },// [UNREACHABLE]
+        }
+    }
+
+    fn short_circuit(&self, op: &str, with: &[NodeWrapper], n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        //
+        // [NOTE] `and` stops at the first `false`, `or` at the first `true`.
+
+        if with.len() < 2 {
+            return Err(MifulError::runtime_error(&format!("Expected at least 2 parameters; got {}!", with.len()), &self.owned_text, n.span).with_code(codes::PARAM_COUNT));
+        }// [ERR] Parameter count
+
+        let stop_at = op == "or";
+
+        for (i, arg) in with.iter().enumerate() {
+            let mut inner_driver = self.sub_driver(vec![arg.clone()], self.scope.clone());
+
+            match inner_driver.next() {
+                Some(Ok(val)) => {
+                    match self.is_true(&val) {
+                        Ok(b) => {
+                            if b == stop_at {
+                                return Ok(self.make_bool(stop_at));
+                            }
+                        },
+
+                        Err(e) => {
+                            return Err(e);
+                        },
+                    }
+                },
+
+                Some(Err(e)) => {
+                    return Err(self.param_eval(e, op, i, arg.span));
+                },
+
+                None => {
+                    unreachable!();
+                },// [UNREACHABLE]
+            }
+        }
+
+        Ok(self.make_bool(!stop_at))
+    }
+
+    //
+    // [END] Function Utils
+
+
+    // [AREA] Module Utils
+    //
+
+    fn module_error(&self, message: &str, code: &'static str, n: &NodeWrapper) -> MifulError {
+        MifulError::runtime_error(message, &self.owned_text, n.span).with_code(code)
+    }
+
+    // [NOTE] Runs the file of `name` unless it already ran, see `modules.rs`.
+    //
+    fn load_module(&self, name: &str, n: &NodeWrapper) -> Result<(PathBuf, Rc<Module>), MifulError> {
+        let path = match self.modules.borrow().resolve(name, &self.path) {
+            Some(path) => { path },
+            None => { return Err(self.module_error(&format!("Cannot find module ` {} `!", name), codes::MODULE_NOT_FOUND, n)); },
+        };// [ERR] Module not found
+
+        if let Some(module) = self.modules.borrow().get(&path) {
+            return Ok((path, module));
+        }
+
+        if let Err(chain) = self.modules.borrow_mut().start(&path) {
+            return Err(self.module_error(&format!("Import cycle: {}!", chain), codes::IMPORT_CYCLE, n));
+        }// [ERR] Import cycle
+
+        let result = self.run_module(&path, n);
+
+        match result {
+            Ok(module) => {
+                self.modules.borrow_mut().finish(&path, Some(module));
+
+                Ok((path.clone(), self.modules.borrow().get(&path).unwrap()))
+            },
+
+            Err(e) => {
+                self.modules.borrow_mut().finish(&path, None);
+
+                let mut new_e = e;
+
+                new_e.add_frame(Frame::named(FrameKind::Import, name, &format!("while importing {}", name), n.span));
+
+                Err(new_e)
+            },
+        }
+    }
+
+    fn run_module(&self, path: &PathBuf, n: &NodeWrapper) -> Result<Module, MifulError> {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => { source },
+            Err(e) => { return Err(self.module_error(&format!("Cannot read ` {} `: {}!", path.display(), e), codes::MODULE_NOT_FOUND, n)); },
+        };// [ERR] Module not readable
+
+        let mut driver = self.over(vec![], Driver::constants(), map!{});
+
+        driver.structs = map!{};
+        driver.top_level = true;
+        driver.path = Some(path.clone());
+
+        if let Err(e) = driver.feed(&source) {
+            return Err(e);
+        }
+
+        for result in driver.by_ref() {
+            if let Err(e) = result {
+                return Err(e);
+            }
+        }
+
+        if let Some(exports) = &driver.exports {
+            for name in exports {
+                if !driver.functions.keys().any(|(f_name, _)| f_name == name) {
+                    return Err(MifulError::runtime_error(&format!("Exported function ` {} ` is not defined!", name), &driver.owned_text, Span::default())
+                        .with_code(codes::UNDEFINED_EXPORT));
+                }// [ERR] Undefined export
+            }
+        }
+
+        // [NOTE] Functions defined by the file itself have a closure without functions,
+        // those imported into it carry their own. Dropping the closure lets a body
+        // see the functions of the file from wherever it gets called.
+        //
+        let mut all_functions: Functions = map!{};
+
+        for (key, (names, body)) in &driver.functions {
+            let mut body = body.clone();
+
+            if body.closure.as_ref().map_or(false, |c| c.functions.is_empty()) {
+                body.closure = None;
+            }
+
+            all_functions.insert(key.clone(), (names.clone(), body));
+        }
+
+        let closure = Rc::new(Closure::new(None, Driver::constants(), all_functions.clone()));
+        let mut functions: Functions = map!{};
+
+        for ((name, types), (names, body)) in &all_functions {
+            let exported = match &driver.exports {
+                Some(exports) => { exports.contains(name) },
+                None => { !name.contains('/') },
+            };
+
+            if exported {
+                let mut body = body.clone();
+
+                if body.closure.is_none() {
+                    body.closure = Some(closure.clone());
+                }
+
+                functions.insert((name.clone(), types.clone()), (names.clone(), body));
+            }
+        }
+
+        Ok(Module {
+            functions,
+            structs: driver.structs,
+        })
+    }
+
+    // [NOTE] Defines the functions of `module`, each prefixed by `namespace/` if given.
+    //
+    fn use_module(&mut self, module: &Module, namespace: Option<&str>, n: &NodeWrapper) -> Result<NodeWrapper, MifulError> {
+        let qualify = |name: &str| {
+            match namespace {
+                Some(ns) => { format!("{}/{}", ns, name) },
+                None => { name.to_owned() },
+            }
+        };
+
+        // [NOTE] Nothing gets defined if any of the functions clashes.
+        //
+        for (name, types) in module.functions.keys() {
+            if let Err(e) = self.check_overload(&qualify(name), types, n.span) {
+                return Err(e);
+            }
+        }
+
+        for ((name, types), val) in &module.functions {
+            let key = (qualify(name), types.clone());
+
+            if !self.top_level {
+                self.locals.insert(key.clone(), val.clone());
+            }
+
+            self.functions.insert(key, val.clone());
+        }
+
+        self.structs.extend(module.structs.clone());
+
+        Ok(self.make_nil())
+    }
+
+    //
+    // [END] Module Utils
+}
+
+
+impl<'a> Iterator for Driver<'a> {
+    type Item = Result<NodeWrapper, MifulError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.ast.len() {
+            None
+
+        } else {
+            let n = self.ast[self.index].clone();
+
+            let kind = n.node.clone();
+
+            let loc_scope = self.scope.clone();
+
+            self.index += 1;
+
+            match kind {
+                NodeKind::Word(_) | NodeKind::Symbol(_) | NodeKind::Str(_) | NodeKind::Int(_) | NodeKind::Float(_) => {
+                    Some(Ok(n))
+                },
+
+                // [TODO] Hooks
+                //
+                NodeKind::List(ref lst) => {
+                    let inner_driver = self.sub_driver(lst.to_vec(), loc_scope);
+                    let result = inner_driver.collect();
+
+                    match result {
+                        Ok(ret) => {
+                            Some(Ok(NodeWrapper::new_list(ret, vec![], n.span)))
+                        },
+
+                        Err(e) => {
+                            let mut new_e = e;
+
+                            new_e.add_frame(Frame::new(FrameKind::Evaluate, "while evaluating list elements", n.span));
+
+                            Some(Err(new_e))
+                        },
+                    }
+                },
+
+                // [TODO] Hooks
+                //
+                NodeKind::Invoke{ target, with } => {
+                    if target == "and" || target == "or" {
+                        //
+                        // Returns whether all (`and`) or any (`or`) of the values are `true`,
+                        // evaluating only as many as needed.
+
+                        return Some(self.short_circuit(&target, &with, &n));
+
+                    } else if target == "let*" {
+                        //
+                        // Runs (quote:2) with every (name value) pair of (list:1) bound in order.
+
+                        return Some(self.let_star(&with, &n));
+                    }
+
+                    let inner_driver = self.sub_driver(with.to_vec(), loc_scope);
+                    let result: Result<Vec<_>, _> = inner_driver
+                        .enumerate()
+                        .map(|(i, arg)| arg.map_err(|e| self.param_eval(e, &target, i, with[i].span)))
+                        .collect();
+
+                    match result {
+                        Ok(mut args) => {
+                            // [NOTE] Errors about an argument point to the expression it came from.
+                            //
+                            for (arg, raw) in args.iter_mut().zip(with.iter()) {
+                                arg.span = raw.span;
+                            }
+
+                            match target.as_ref() {
+                                "print" | "eprint" => {
+                                    //
+                                    // Prints (word:1) or (symbol:1) or ((obj string):1)
+
+                                    if args.len() == 1 {
+                                        Some(self.print_fn(args[0].clone(), target == "eprint"))
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "flush" => {
+                                    //
+                                    // Writes out everything printed so far
+
+                                    if args.is_empty() {
+                                        match self.console.flush() {
+                                            Ok(_) => { Some(Ok(self.make_nil())) },
+                                            Err(e) => { Some(Err(self.io_error(e, n.span))) },
+                                        }// [ERR] Console
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(0, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "input" => {
+                                    //
+                                    // Prints (printable:1), reads line from the console, and returns it as (str)
+                                    //
+                                    // [NOTE] The line break is not included.
+
+                                    if args.len() == 1 {
+                                        let result = self.print_fn(args[0].clone(), false);
+
+                                        match result {
+                                            Ok(_v) => {
+                                                let _ = self.console.flush();
+
+                                                match self.console.read_line() {
+                                                    Ok(Some(line)) => {
+                                                        Some(Ok(self.make_str(&line, &n)))
+                                                    },
+
+                                                    Ok(None) => {
+                                                        Some(Err(MifulError::runtime_error("Unexpected end of input!", &self.owned_text, n.span)
+                                                            .with_code(codes::END_OF_INPUT)))
+                                                    },// [ERR] End of input
+
+                                                    Err(e) => {
+                                                        Some(Err(self.io_error(e, n.span)))
+                                                    },// [ERR] Console
+                                                }
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "args" => {
+                                    //
+                                    // Returns the arguments of the script as (list<str>)
+
+                                    if args.is_empty() {
+                                        let script_args = self.args.iter().map(|a| self.make_str(a, &n)).collect();
+
+                                        Some(Ok(NodeWrapper::new_list(script_args, vec![], n.span)))
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(0, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "mk-sym" => {
+                                    //
+                                    // Creates a symbol from (word:1)
+                                    //
+                                    // [NOTE] Synonymous with `(word:1)`
+
+                                    if args.len() == 1 {
+                                        if let NodeKind::Word(w) | NodeKind::Str(w) = &args[0].node {
+                                            Some(Ok(NodeWrapper::new_symbol(w.to_owned(), n.span)))
+
+                                        } else if let NodeKind::Int(i) = &args[0].node {
+                                            Some(Ok(NodeWrapper::new_symbol(i.to_string(), n.span)))
+
+                                        } else if let NodeKind::Float(f) = &args[0].node {
+                                            Some(Ok(NodeWrapper::new_symbol(f.to_string(), n.span)))
+
+                                        } else {
+                                            Some(Err(self.param_type("word", args[0].span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "import" | "require" => {
+                                    //
+                                    // Runs the file (str:1) once and defines its exported functions,
+                                    // qualified by (word:2) or the file name for `import`
+
+                                    let max_count = if target == "import" { 2 } else { 1 };
+
+                                    if !args.is_empty() && args.len() <= max_count {
+                                        if let NodeKind::Str(name) = &args[0].node {
+                                            let namespace = match args.get(1).map(|a| &a.node) {
+                                                Some(NodeKind::Word(ns)) | Some(NodeKind::Symbol(ns)) => { Some(ns.to_owned()) },
+                                                Some(_) => { return Some(Err(self.param_type("word", args[1].span))); },
+
+                                                None => { None },
+                                            };// [ERR] 2nd parameter type
+
+                                            match self.load_module(name, &n) {
+                                                Ok((path, module)) => {
+                                                    let namespace = if target == "require" {
+                                                        None
+
+                                                    } else {
+                                                        namespace.or_else(|| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+                                                    };
+
+                                                    Some(self.use_module(&module, namespace.as_deref(), &n))
+                                                },
+
+                                                Err(e) => {
+                                                    Some(Err(e))
+                                                },
+                                            }
+
+                                        } else {
+                                            Some(Err(self.param_type("str", args[0].span)))
+                                        }// [ERR] 1st parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_range(1, max_count, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "export" => {
+                                    //
+                                    // Exports only the functions named (word:1) (word:2) ... from the file
+
+                                    if !self.top_level {
+                                        return Some(Err(MifulError::runtime_error("Only the top level can export!", &self.owned_text, n.span).with_code(codes::MISPLACED_EXPORT)));
+                                    }// [ERR] Export below top level
+
+                                    let mut names = vec![];
+
+                                    for arg in &args {
+                                        if let NodeKind::Word(name) | NodeKind::Symbol(name) = &arg.node {
+                                            names.push(name.to_owned());
+
+                                        } else {
+                                            return Some(Err(self.param_type("word", arg.span)));
+                                        }// [ERR] Parameter type
+                                    }
+
+                                    self.exports.get_or_insert(vec![]).extend(names);
+
+                                    Some(Ok(self.make_nil()))
+                                },
+
+                                ":" => {
+                                    //
+                                    // Returns the value in scope under the name of (word:1)
+
+                                    if args.len() == 1 {
+                                        if let NodeKind::Word(v) | NodeKind::Symbol(v) = &args[0].node {
+                                            if let Some(val) = self.scope.get(v) {
+                                                Some(Ok(val.clone()))
+
+                                            } else {
+                                                Some(Err(MifulError::runtime_error("Undefined constant!", &self.owned_text, n.span).with_code(codes::UNDEFINED_CONSTANT)))
+                                            }// [ERR] Undefined constant
+
+                                        } else {
+                                            Some(Err(self.param_type("(word | symbol)", n.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "=" => {
+                                    //
+                                    // Returns whether (val:1) and (val:2) are equal.
+
+                                    if args.len() == 2 {
+                                        Some(self.values_equal(&args[0], &args[1]))
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "<" | ">" | "<=" | ">=" => {
+                                    //
+                                    // Returns whether (val:1) is less than, greater than, at most,
+                                    // or at least (val:2).
+
+                                    if args.len() == 2 {
+                                        match self.compare_values(&args[0], &args[1]) {
+                                            Ok(ord) => {
+                                                let holds = match target.as_ref() {
+                                                    "<" => ord == Ordering::Less,
+                                                    ">" => ord == Ordering::Greater,
+                                                    "<=" => ord != Ordering::Greater,
+                                                    _ => ord != Ordering::Less,
+                                                };
+
+                                                Some(Ok(self.make_bool(holds)))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "not" => {
+                                    //
+                                    // Returns `true` if (val:1) is `false`, and vice versa.
+
+                                    if args.len() == 1 {
+                                        match self.is_true(&args[0]) {
+                                            Ok(b) => {
+                                                Some(Ok(self.make_bool(!b)))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                // [TODO] Hooks
+                                //
+                                "+" => {
+                                    //
+                                    // Returns (int:1) + (int:2), (float:1) + (float:2),
+                                    // or is synonymous with [obj-append [:obj1] [obj-unwrap [:obj2]]],
+                                    // where (obj:1) and (obj:2) are of the same type.
+
+                                    if args.len() == 2 {
+                                        let a = &args[0];
+                                        let b = &args[1];
+
+                                        match (&a.node, &b.node) {
+                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
+                                                Some(Ok(NodeWrapper::new_int(i1 + i2, n.span)))
+                                            },
+
+                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
+                                                Some(Ok(NodeWrapper::new_float(f1 + f2, n.span)))
+                                            },
+
+                                            (NodeKind::Str(s1), NodeKind::Str(s2)) => {
+                                                Some(Ok(self.make_str(&(s1.to_owned() + s2), &n)))
+                                            },
+
+                                            (NodeKind::List(l1), NodeKind::List(l2)) => {
+                                                if self.check_obj_type(&a.node, "any")
+                                                    && self.check_obj_type(&b.node, "any") {
+
+                                                    let t1 = self.get_obj_type(&l1[1]).unwrap();
+                                                    let t2 = self.get_obj_type(&l2[1]).unwrap();
+
+                                                    if t1 == t2 {
+                                                        Some(Ok(self.obj_append(a, l2.to_vec()).unwrap()))
+
+                                                    } else {
+                                                        Some(Err(MifulError::runtime_error("Can't concat two different objects!", &self.owned_text, n.span).with_code(codes::DIFFERENT_OBJECTS)))
+                                                    }// [ERR] Different objects concat
+
+                                                } else {
+                                                    let mut new_l = l1.clone();
+                                                    let mut new_hooks = a.hooks.clone();
+
+                                                    new_l.append(&mut l2.clone());
+                                                    new_hooks.append(&mut b.hooks.clone());
+
+                                                    Some(Ok(NodeWrapper::new_list(new_l, new_hooks, n.span)))
+                                                }
+                                            },
+
+                                            _ => {
+                                                let a_ok = matches!(a.node, NodeKind::Int(_) | NodeKind::Float(_) | NodeKind::Str(_) | NodeKind::List(_));
+
+                                                Some(Err(self.operand_type("(int | float | str | (obj any) | list)", a_ok, a, b)))
+                                            }// [ERR] Parameter type
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "-" => {
+                                    //
+                                    // Returns (int:1) - (int:2) or (float:1) - (float:2).
+
+                                    if args.len() == 2 {
+                                        let a = &args[0];
+                                        let b = &args[1];
+
+                                        match (&a.node, &b.node) {
+                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
+                                                Some(Ok(NodeWrapper::new_int(i1 - i2, n.span)))
+                                            },
+
+                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
+                                                Some(Ok(NodeWrapper::new_float(f1 - f2, n.span)))
+                                            },
+
+                                            _ => {
+                                                Some(Err(self.operand_type("(int | float)", self.is_number(a), a, b)))
+                                            }// [ERR] Parameter type
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "*" => {
+                                    //
+                                    // Returns (int:1) * (int:2), (float:1) * (float:2).
+
+                                    if args.len() == 2 {
+                                        let a = &args[0];
+                                        let b = &args[1];
+
+                                        match (&a.node, &b.node) {
+                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
+                                                Some(Ok(NodeWrapper::new_int(i1 * i2, n.span)))
+                                            },
+
+                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
+                                                Some(Ok(NodeWrapper::new_float(f1 * f2, n.span)))
+                                            },
+
+                                            _ => {
+                                                Some(Err(self.operand_type("(int | float)", self.is_number(a), a, b)))
+                                            }// [ERR] Parameter type
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "/" => {
+                                    //
+                                    // Returns (int:1) / (int:2) rounded down, or (float:1) / (float:2).
+
+                                    if args.len() == 2 {
+                                        let a = &args[0];
+                                        let b = &args[1];
+
+                                        match (&a.node, &b.node) {
+                                            (NodeKind::Int(_), NodeKind::Int(0)) => {
+                                                Some(Err(self.division_by_zero(b)))
+                                            },// [ERR] Division by zero
+
+                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
+                                                if let Some(q) = i1.checked_div(*i2) {
+                                                    let floored = if i1 % i2 != 0 && (*i1 < 0) != (*i2 < 0) { q - 1 } else { q };
+
+                                                    Some(Ok(NodeWrapper::new_int(floored, n.span)))
+
+                                                } else {
+                                                    Some(Err(MifulError::runtime_error("Integer overflow!", &self.owned_text, n.span).with_code(codes::INTEGER_OVERFLOW)))
+                                                }// [ERR] Integer overflow
+                                            },
+
+                                            (NodeKind::Float(_), NodeKind::Float(f2)) if *f2 == 0.0 => {
+                                                Some(Err(self.division_by_zero(b)))
+                                            },// [ERR] Division by zero
+
+                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
+                                                Some(Ok(NodeWrapper::new_float(f1 / f2, n.span)))
+                                            },
+
+                                            _ => {
+                                                Some(Err(self.operand_type("(int | float)", self.is_number(a), a, b)))
+                                            }// [ERR] Parameter type
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "%" => {
+                                    //
+                                    // Returns the remainder of rounded down (int:1) / (int:2) or (float:1) / (float:2).
+                                    //
+                                    // [NOTE] The remainder has the sign of the divisor.
+
+                                    if args.len() == 2 {
+                                        let a = &args[0];
+                                        let b = &args[1];
+
+                                        match (&a.node, &b.node) {
+                                            (NodeKind::Int(_), NodeKind::Int(0)) => {
+                                                Some(Err(self.division_by_zero(b)))
+                                            },// [ERR] Division by zero
+
+                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
+                                                let r = i1.wrapping_rem(*i2);
+                                                let floored = if r != 0 && (r < 0) != (*i2 < 0) { r + i2 } else { r };
+
+                                                Some(Ok(NodeWrapper::new_int(floored, n.span)))
+                                            },
+
+                                            (NodeKind::Float(_), NodeKind::Float(f2)) if *f2 == 0.0 => {
+                                                Some(Err(self.division_by_zero(b)))
+                                            },// [ERR] Division by zero
+
+                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
+                                                Some(Ok(NodeWrapper::new_float(f1 - f2 * (f1 / f2).floor(), n.span)))
+                                            },
+
+                                            _ => {
+                                                Some(Err(self.operand_type("(int | float)", self.is_number(a), a, b)))
+                                            }// [ERR] Parameter type
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "floor" | "ceil" | "round" => {
+                                    //
+                                    // Returns (float:1) rounded down, up, or to the nearest integer, as (int).
+                                    //
+                                    // [NOTE] (int:1) is returned unchanged.
+
+                                    if args.len() == 1 {
+                                        let val = &args[0];
+
+                                        match &val.node {
+                                            NodeKind::Int(i) => {
+                                                Some(Ok(NodeWrapper::new_int(*i, n.span)))
+                                            },
+
+                                            NodeKind::Float(f) => {
+                                                let rounded = match target.as_ref() {
+                                                    "floor" => f.floor(),
+                                                    "ceil" => f.ceil(),
+                                                    _ => f.round(),
+                                                };
+
+                                                Some(self.float_to_int(rounded, &n))
+                                            },
+
+                                            _ => {
+                                                Some(Err(self.param_type("(int | float)", val.span)))
+                                            }// [ERR] Parameter type
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "if" => {
+                                    //
+                                    // Runs (quote:2) when (value:1) is `true`, or runs (quote:3) otherwise.
+
+                                    if args.len() == 3 {
+                                        let cond_node = &args[0];
+                                        let true_node = &args[1];
+                                        let false_node = &args[2];
+
+                                        if let NodeKind::Quote{ target: t_target, with: t_with } = &true_node.node {
+                                            if let NodeKind::Quote{ target: f_target, with: f_with } = &false_node.node {
+                                                if let NodeKind::Symbol(s) = &cond_node.node {
+                                                    if s == "true" {
+                                                        let t_invoke = NodeWrapper::new_invoke(t_target.to_string(),
+                                                            t_with.to_vec(), true_node.hooks.clone(), true_node.span);
+
+                                                        let inner_driver = self.closure_driver(vec![t_invoke], &true_node.closure, map!{});
+                                                        let arg_result: Result<Vec<_>, _> = inner_driver.collect();
+
+                                                        match arg_result {
+                                                            Ok(ret) => {
+                                                                return Some(Ok(ret[0].clone()));
+                                                            },
+
+                                                            Err(e) => {
+                                                                let mut new_e = e;
+
+                                                                new_e.add_frame(Frame::new(FrameKind::Evaluate, "while evaluating the branch of if", true_node.span));
+
+                                                                return Some(Err(new_e));
+                                                            }// [ERR] While param eval
+                                                        }
+                                                    }
+                                                }
+
+                                                let f_invoke = NodeWrapper::new_invoke(f_target.to_string(),
+                                                    f_with.to_vec(), false_node.hooks.clone(), false_node.span);
+
+                                                let inner_driver = self.closure_driver(vec![f_invoke], &false_node.closure, map!{});
+                                                let arg_result: Result<Vec<_>, _> = inner_driver.collect();
+
+                                                match arg_result {
+                                                    Ok(ret) => {
+                                                        Some(Ok(ret[0].clone()))
+                                                    },
+
+                                                    Err(e) => {
+                                                        let mut new_e = e;
+
+                                                        new_e.add_frame(Frame::new(FrameKind::Evaluate, "while evaluating the branch of if", false_node.span));
+
+                                                        Some(Err(new_e))
+                                                    }// [ERR] While param eval
+                                                }
+
+                                            } else {
+                                                Some(Err(self.param_type("quote", true_node.span)))
+                                            }// [ERR] Parameter type
+
+                                        } else {
+                                            Some(Err(self.param_type("quote", true_node.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "let" => {
+                                    //
+                                    // Runs (quote:3) with (value:2) bound to the name (word:1).
+                                    //
+                                    // [NOTE] The binding shadows any outer value of the same name.
+
+                                    if args.len() == 3 {
+                                        match self.binding_name(&args[0]) {
+                                            Ok(name) => {
+                                                Some(self.eval_quote(&args[2], map!{ name => args[1].clone() }))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "lambda" => {
+                                    //
+                                    // Returns (quote:2) taking parameters ((list *(list (word type))):1),
+                                    // closed over the current scope.
+
+                                    if args.len() == 2 {
+                                        let raw_sig = &args[0];
+                                        let body = &args[1];
+
+                                        if let NodeKind::List(raw_lst) = &raw_sig.node {
+                                            if let (NodeKind::Quote{ target: _, with: _ }, Some(c)) = (&body.node, &body.closure) {
+                                                match self.parse_signature(raw_lst) {
+                                                    Ok((names, types)) => {
+                                                        let params = names.into_iter().zip(types).collect();
+                                                        let closure = Closure::new(Some(params), c.scope.clone(), c.functions.clone());
+
+                                                        Some(Ok(body.clone().with_closure(closure)))
+                                                    },
+
+                                                    Err(e) => {
+                                                        let mut new_e = e;
+
+                                                        new_e.add_frame(Frame::new(FrameKind::Define, "while creating lambda", n.span));
+
+                                                        Some(Err(new_e))
+                                                    },
+                                                }
+
+                                            } else {
+                                                Some(Err(self.param_type("quote", body.span)))
+                                            }// [ERR] Parameter type
+
+                                        } else {
+                                            Some(Err(self.param_type("(list *(list (word type)))", raw_sig.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "apply" => {
+                                    //
+                                    // Runs (quote:1) with arguments (list:2).
+
+                                    if args.len() == 2 {
+                                        if let NodeKind::List(lst) = &args[1].node {
+                                            Some(self.apply_quote(&args[0], lst.to_vec(), &n))
+
+                                        } else {
+                                            Some(Err(self.param_type("list", args[1].span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "call" => {
+                                    //
+                                    // Runs (quote:1) with the rest of the parameters as arguments.
+
+                                    if !args.is_empty() {
+                                        Some(self.apply_quote(&args[0], args[1..].to_vec(), &n))
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "return" => {
+                                    //
+                                    // Returns the given (value:1)
+
+                                    if args.len() == 1 {
+                                        Some(Ok(args[0].clone()))
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "define" => {
+                                    //
+                                    // Adds to the function scope a new function with the name (word:1),
+                                    // parameter signature ((tuple *(tuple (word, type))):2),
+                                    // and body (quote:3).
+
+                                    if with.len() == 3 {
+                                        let raw_1 = &args[0];
+                                        let raw_2 = &args[1];
+                                        let raw_3 = &args[2];// [TODO] Substitute into locally defined functions
+
+                                        if let NodeKind::Word(def_name) | NodeKind::Symbol(def_name) = &raw_1.node {
+                                            if let NodeKind::List(raw_lst) = &raw_2.node {
+                                                if let NodeKind::Quote{ target, with: params } = &raw_3.node {
+                                                    let mut body_invoke = NodeWrapper::new_invoke(target.to_owned(), params.to_vec(), raw_3.hooks.clone(), raw_3.span);
+
+                                                    body_invoke.closure = raw_3.closure.clone();
+
+                                                    Some(self.define_function(def_name, raw_lst.to_vec(), body_invoke, &n))
+
+                                                } else {
+                                                    Some(Err(self.param_type("quote", raw_3.span)))
+                                                }// [ERR] 3rd parameter type
+
+                                            } else {
+                                                Some(Err(self.param_type("(list *(list (word type)))", raw_2.span)))
+                                            }// [ERR] 2nd parameter type
+
+                                        } else {
+                                            Some(Err(self.param_type("(word | symbol)", raw_1.span)))
+                                        }// [ERR] 1st parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "undefine" => {
+                                    //
+                                    // Removes every overload of the function named (word:1),
+                                    // or only the one with parameter types (list:2).
+
+                                    if args.len() == 1 || args.len() == 2 {
+                                        match self.binding_name(&args[0]) {
+                                            Ok(name) => {
+                                                if args.len() == 1 {
+                                                    Some(self.undefine_function(&name, None, &n))
+
+                                                } else if let NodeKind::List(raw_types) = &args[1].node {
+                                                    match self.list_to_types(raw_types) {
+                                                        Ok(types) => {
+                                                            Some(self.undefine_function(&name, Some(types), &n))
+                                                        },
+
+                                                        Err(e) => {
+                                                            let mut new_e = e;
+
+                                                            new_e.add_frame(Frame::named(FrameKind::Define, &name, &format!("while undefining function {}", name), n.span));
+
+                                                            Some(Err(new_e))
+                                                        },
+                                                    }
+
+                                                } else {
+                                                    Some(Err(self.param_type("(list *type)", args[1].span)))
+                                                }// [ERR] Parameter type
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_range(1, 2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "defined?" => {
+                                    //
+                                    // Returns whether there is a user function named (word:1).
+
+                                    if args.len() == 1 {
+                                        match self.binding_name(&args[0]) {
+                                            Ok(name) => {
+                                                Some(Ok(self.make_bool(!self.overloads_of(&name).is_empty())))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "overloads" => {
+                                    //
+                                    // Returns the signatures of all user functions named (word:1), as a list of symbols.
+
+                                    if args.len() == 1 {
+                                        match self.binding_name(&args[0]) {
+                                            Ok(name) => {
+                                                let sigs = self.overloads_of(&name)
+                                                    .iter()
+                                                    .map(|types| NodeWrapper::new_symbol(self.format_signature(&name, types), n.span))
+                                                    .collect();
+
+                                                Some(Ok(NodeWrapper::new_list(sigs, vec![], n.span)))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "struct" => {
+                                    //
+                                    // Declares a structure named (word:1) with fields ((list *(list (word type))):2).
+
+                                    if args.len() == 2 {
+                                        match self.binding_name(&args[0]) {
+                                            Ok(name) => {
+                                                if let NodeKind::List(raw_fields) = &args[1].node {
+                                                    Some(self.declare_struct(&name, raw_fields, &n))
+
+                                                } else {
+                                                    Some(Err(self.param_type("(list *(list (word type)))", args[1].span)))
+                                                }// [ERR] Parameter type
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "new" => {
+                                    //
+                                    // Returns an object of structure (word:1) with field values (list:2), in declaration order.
+
+                                    if args.len() == 2 {
+                                        match self.binding_name(&args[0]) {
+                                            Ok(name) => {
+                                                if let NodeKind::List(values) = &args[1].node {
+                                                    Some(self.construct_struct(&name, values, &n))
+
+                                                } else {
+                                                    Some(Err(self.param_type("list", args[1].span)))
+                                                }// [ERR] Parameter type
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "@" => {
+                                    //
+                                    // Returns the field or the associated function named (word:2) of (obj:1).
+
+                                    if args.len() == 2 {
+                                        match self.binding_name(&args[1]) {
+                                            Ok(member) => {
+                                                Some(self.member_access(&args[0], &member, &n))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "update" => {
+                                    //
+                                    // Returns a copy of (obj:1) with field (word:2) set to (value:3).
+
+                                    if args.len() == 3 {
+                                        match self.binding_name(&args[1]) {
+                                            Ok(field) => {
+                                                Some(self.update_struct(&args[0], &field, &args[2], &n))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "obj-append" => {
+                                    //
+                                    // Appends (list:2) to the contents of (obj:1)
+
+                                    if args.len() == 2 {
+                                        let obj = &args[0];
+                                        let lst_node = &args[1];
+
+                                        if let NodeKind::List(lst) = &lst_node.node {
+                                            Some(self.obj_append(obj, lst.to_vec()))
+
+                                        } else {
+                                            Some(Err(self.param_type("list", lst_node.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, with.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "length" => {
+                                    //
+                                    // Returns (int), which is the length of (tuple:1)
+
+                                    if args.len() == 1 {
+                                        let lst_node = &args[0];
+
+                                        if let NodeKind::List(lst) = &lst_node.node {
+                                            Some(Ok(NodeWrapper::new_int(lst.len() as i64, n.span)))
+
+                                        } else if let NodeKind::Str(s) = &lst_node.node {
+                                            Some(Ok(NodeWrapper::new_int(segment_text(s).len() as i64, n.span)))
+
+                                        } else {
+                                            Some(Err(self.param_type("(list (any))", lst_node.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "head" => {
+                                    //
+                                    // Returns the first element of (list:1)
+
+                                    if args.len() == 1 {
+                                        let lst_node = &args[0];
+
+                                        if let NodeKind::List(lst) = &lst_node.node {
+                                            if !lst.is_empty() {
+                                                Some(Ok(lst[0].clone()))
+
+                                            } else {
+                                                Some(Err(MifulError::runtime_error("Cannot get head of empty tuple!", &self.owned_text, lst_node.span).with_code(codes::EMPTY_LIST)))
+                                            }// [ERR] Head of empty tuple
+
+                                        } else {
+                                            Some(Err(self.param_type("(list (any))", lst_node.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "tail" => {
+                                    //
+                                    // Returns the (list:1) without head.
+
+                                    if args.len() == 1 {
+                                        let lst_node = &args[0];
+
+                                        if let NodeKind::List(lst) = &lst_node.node {
+                                            if !lst.is_empty() {
+                                                let (_, tail) = lst.split_first().unwrap();
+
+                                                Some(Ok(NodeWrapper::new_list(tail.to_vec(), lst_node.hooks.clone(), n.span)))
+
+                                            } else {
+                                                Some(Err(MifulError::runtime_error("Cannot get tail of empty tuple!", &self.owned_text, lst_node.span).with_code(codes::EMPTY_LIST)))
+                                            }// [ERR] Head of empty tuple
+
+                                        } else {
+                                            Some(Err(self.param_type("(list (any))", lst_node.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "reverse" => {
+                                    //
+                                    // Returns the (list:1), reversed.
+
+                                    if args.len() == 1 {
+                                        let lst_node = &args[0];
+
+                                        if let NodeKind::List(lst) = &lst_node.node {
+                                            let mut mut_lst = lst.clone();
+
+                                            mut_lst.reverse();
+
+                                            Some(Ok(NodeWrapper::new_list(mut_lst, lst_node.hooks.clone(), n.span)))
+
+                                        } else {
+                                            Some(Err(self.param_type("(list (any))", lst_node.span)))
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "map" | "filter" => {
+                                    //
+                                    // Returns (list:1) with (quote:2) applied to every element,
+                                    // or only the elements for which (quote:2) returns `true`.
+
+                                    if args.len() == 2 {
+                                        match self.expect_list(&args[0]) {
+                                            Ok(lst) => {
+                                                if target == "map" {
+                                                    Some(self.map_list(lst, &args[1], &n))
+
+                                                } else {
+                                                    Some(self.filter_list(lst, &args[1], &n))
+                                                }
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "fold" => {
+                                    //
+                                    // Returns the result of applying (quote:3) to the accumulator, starting
+                                    // at (value:2), and every element of (list:1), from left to right.
+
+                                    if args.len() == 3 {
+                                        match self.expect_list(&args[0]) {
+                                            Ok(lst) => {
+                                                Some(self.fold_list(lst, &args[1], &args[2], &n))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "zip" => {
+                                    //
+                                    // Returns the list of pairs of elements of (list:1) and (list:2).
+                                    //
+                                    // [NOTE] The result is as long as the shorter list.
+
+                                    if args.len() == 2 {
+                                        match (self.expect_list(&args[0]), self.expect_list(&args[1])) {
+                                            (Ok(l1), Ok(l2)) => {
+                                                let pairs = l1.into_iter()
+                                                    .zip(l2)
+                                                    .map(|(a, b)| NodeWrapper::new_list(vec![a, b], vec![], n.span))
+                                                    .collect();
+
+                                                Some(Ok(NodeWrapper::new_list(pairs, vec![], n.span)))
+                                            },
+
+                                            (Err(e), _) | (_, Err(e)) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "range" => {
+                                    //
+                                    // Returns the list of ints from (int:1) up to, but not including, (int:2).
+
+                                    if args.len() == 2 {
+                                        match (&args[0].node, &args[1].node) {
+                                            (NodeKind::Int(from), NodeKind::Int(to)) => {
+                                                if *to as i128 - *from as i128 > MAX_RANGE {
+                                                    return Some(Err(MifulError::runtime_error(
+                                                        &format!("Range from {} to {} is longer than {} ints!", from, to, MAX_RANGE),
+                                                        &self.owned_text,
+                                                        args[1].span
+                                                    ).with_code(codes::RANGE_TOO_LARGE)));
+                                                }// [ERR] Range too large
+
+                                                let ints = (*from .. *to)
+                                                    .map(|i| NodeWrapper::new_int(i, n.span))
+                                                    .collect();
+
+                                                Some(Ok(NodeWrapper::new_list(ints, vec![], n.span)))
+                                            },
+
+                                            (NodeKind::Int(_), _) => { Some(Err(self.param_type("int", args[1].span))) },
+                                            _ => { Some(Err(self.param_type("int", args[0].span))) },
+                                        }// [ERR] Parameter type
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "nth" => {
+                                    //
+                                    // Returns the element of (list:1) at index (int:2), counting from 0.
+
+                                    if args.len() == 2 {
+                                        match (self.expect_list(&args[0]), self.expect_index(&args[1])) {
+                                            (Ok(lst), Ok(i)) => {
+                                                if i < lst.len() {
+                                                    Some(Ok(lst[i].clone()))
+
+                                                } else {
+                                                    Some(Err(MifulError::runtime_error(
+                                                        &format!("Index {} out of range for length {}!", i, lst.len()),
+                                                        &self.owned_text,
+                                                        args[1].span
+                                                    ).with_code(codes::INDEX_OUT_OF_RANGE)))
+                                                }// [ERR] Index out of range
+                                            },
+
+                                            (Err(e), _) | (_, Err(e)) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "take" | "drop" => {
+                                    //
+                                    // Returns the first (int:2) elements of (list:1), or the rest of them.
+                                    //
+                                    // [NOTE] Ignores index overflow.
+
+                                    if args.len() == 2 {
+                                        match (self.expect_list(&args[0]), self.expect_index(&args[1])) {
+                                            (Ok(lst), Ok(i)) => {
+                                                let (front, back) = lst.split_at(i.min(lst.len()));
+                                                let part = if target == "take" { front } else { back };
+
+                                                Some(Ok(NodeWrapper::new_list(part.to_vec(), vec![], n.span)))
+                                            },
+
+                                            (Err(e), _) | (_, Err(e)) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "concat" => {
+                                    //
+                                    // Returns the elements of all lists in (list:1), joined into one list,
+                                    // or all strings in (list:1), joined into one string.
+                                    //
+                                    // [NOTE] An empty (list:1) joins no lists, not no strings.
+
+                                    if args.len() == 1 {
+                                        match self.expect_list(&args[0]) {
+                                            Ok(ref strs) if !strs.is_empty() && strs.iter().all(|v| self.check_type(v, &MifulType::Simple("str".to_owned()))) => {
+                                                let joined: String = strs.iter().map(|v| self.to_text(v)).collect();
+
+                                                Some(Ok(self.make_str(&joined, &n)))
+                                            },
+
+                                            Ok(lsts) => {
+                                                let mut joined = vec![];
+
+                                                for lst in &lsts {
+                                                    match self.expect_list(lst) {
+                                                        Ok(inner) => { joined.extend(inner); },
+                                                        Err(e) => { return Some(Err(e)); },
+                                                    }
+                                                }
+
+                                                Some(Ok(NodeWrapper::new_list(joined, vec![], n.span)))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "sort" => {
+                                    //
+                                    // Returns (list:1) in ascending order, as compared by `<`.
+
+                                    if args.len() == 1 {
+                                        match self.expect_list(&args[0]) {
+                                            Ok(lst) => {
+                                                Some(self.sort_list(lst, &n))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "contains" => {
+                                    //
+                                    // Returns whether (list:1) has an element equal to (value:2).
+
+                                    if args.len() == 2 {
+                                        match self.expect_list(&args[0]) {
+                                            Ok(lst) => {
+                                                for val in &lst {
+                                                    match self.values_equal(val, &args[1]) {
+                                                        Ok(ret) => {
+                                                            if let NodeKind::Symbol(b) = &ret.node {
+                                                                if b == "true" {
+                                                                    return Some(Ok(self.make_true()));
+                                                                }
+                                                            }
+                                                        },
+
+                                                        Err(e) => {
+                                                            return Some(Err(e));
+                                                        },
+                                                    }
+                                                }
+
+                                                Some(Ok(self.make_false()))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "split" => {
+                                    //
+                                    // Returns the parts of (str:1) between occurrences of (str:2).
+                                    //
+                                    // [NOTE] Empty (str:2) splits into graphemes.
+
+                                    if args.len() == 2 {
+                                        match (self.expect_str(&args[0]), self.expect_str(&args[1])) {
+                                            (Ok(s), Ok(sep)) => {
+                                                Some(Ok(self.split_str(&s, &sep, &n)))
+                                            },
+
+                                            (Err(e), _) | (_, Err(e)) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "substring" => {
+                                    //
+                                    // Returns the part of (str:1) from index (int:2) up to, but not including, (int:3).
+                                    //
+                                    // [NOTE] Ignores index overflow.
+
+                                    if args.len() == 3 {
+                                        match (self.expect_str(&args[0]), self.expect_index(&args[1]), self.expect_index(&args[2])) {
+                                            (Ok(s), Ok(from), Ok(to)) => {
+                                                Some(Ok(self.make_str(&self.substring(&s, from, to), &n)))
+                                            },
+
+                                            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "split_at" => {
+                                    //
+                                    // Returns the first (int:2) graphemes of (str:1) and the rest, as a 2-tuple.
+                                    //
+                                    // [NOTE] Ignores index overflow.
+
+                                    if args.len() == 2 {
+                                        match (self.expect_str(&args[0]), self.expect_index(&args[1])) {
+                                            (Ok(s), Ok(i)) => {
+                                                let front = self.make_str(&self.substring(&s, 0, i), &n);
+                                                let back = self.make_str(&self.substring(&s, i, usize::MAX), &n);
+
+                                                Some(Ok(NodeWrapper::new_list(vec![front, back], vec![], n.span)))
+                                            },
+
+                                            (Err(e), _) | (_, Err(e)) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "to-upper" | "to-lower" | "trim" => {
+                                    //
+                                    // Returns (str:1) in upper case, in lower case, or without surrounding whitespace.
+
+                                    if args.len() == 1 {
+                                        match self.expect_str(&args[0]) {
+                                            Ok(s) => {
+                                                let changed = match target.as_ref() {
+                                                    "to-upper" => s.to_uppercase(),
+                                                    "to-lower" => s.to_lowercase(),
+                                                    _ => s.trim().to_owned(),
+                                                };
+
+                                                Some(Ok(self.make_str(&changed, &n)))
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "format" => {
+                                    //
+                                    // Returns (str:1) with every `{}` replaced by the next value of (list:2).
+
+                                    if args.len() == 2 {
+                                        match (self.expect_str(&args[0]), self.expect_list(&args[1])) {
+                                            (Ok(template), Ok(values)) => {
+                                                Some(self.format_str(&template, &values, &n))
+                                            },
+
+                                            (Err(e), _) | (_, Err(e)) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(2, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "to-str" => {
+                                    //
+                                    // Returns (value:1) as (str).
+
+                                    if args.len() == 1 {
+                                        Some(Ok(self.make_str(&self.to_text(&args[0]), &n)))
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                "to-int" | "to-float" => {
+                                    //
+                                    // Returns the number written in (str:1).
+
+                                    if args.len() == 1 {
+                                        match self.expect_str(&args[0]) {
+                                            Ok(s) => {
+                                                let parsed = match (Lexer::parse_number(s.trim()), target.as_ref()) {
+                                                    (Some(Ok(tok::TokenType::Int(i))), "to-int") => { Ok(NodeWrapper::new_int(i, n.span)) },
+                                                    (Some(Ok(tok::TokenType::Int(i))), _) => { Ok(NodeWrapper::new_float(i as f64, n.span)) },
+                                                    (Some(Ok(tok::TokenType::Float(f))), "to-float") => { Ok(NodeWrapper::new_float(f, n.span)) },
+
+                                                    (Some(Err(message)), _) => { Err(message) },
+                                                    _ => { Err(format!("Cannot convert {} to {}!", escape_str(&s), &target[3 ..])) },
+                                                };
+
+                                                match parsed {
+                                                    Ok(num) => { Some(Ok(num)) },
+
+                                                    Err(message) => {
+                                                        Some(Err(MifulError::runtime_error(&message, &self.owned_text, args[0].span).with_code(codes::INVALID_CONVERSION)))
+                                                    },// [ERR] Invalid number
+                                                }
+                                            },
+
+                                            Err(e) => {
+                                                Some(Err(e))
+                                            },
+                                        }
+
+                                    } else {
+                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
+                                    }// [ERR] Parameter count
+                                },
+
+                                f_name => {
+                                    Some(self.call_function(f_name, args, &n))
+                                },
+                            }
+                        },
+
+                        Err(e) => {
+                            Some(Err(e))
+                        }// [ERR] Param eval
+                    }
+                },
+
+                NodeKind::Quote{ target, with } => {
+                    if n.closure.is_some() {
+                        return Some(Ok(n));
+                    }// [NOTE] Already a value.
+
+                    let mut new_with = vec![];
+
+                    for arg in with {
+                        match self.resolve_hooks(arg) {
+                            Ok(new_arg) => { new_with.push(new_arg); },
+                            Err(e) => { return Some(Err(e)); },
+                        }
+                    }
 
-                    match result {
-                        Ok(args) => {
-                            match target.as_ref() {
-                                "print" => {
-                                    //
-                                    // Prints (word:1) or (symbol:1) or ((obj string):1)
+                    let closure = Closure::new(None, self.scope.clone(), self.locals.clone());
 
-                                    if args.len() == 1 {
-                                        Some(self.print_fn(args[0].clone()))
+                    Some(Ok(NodeWrapper::new_quote(target, new_with, vec![], n.span).with_closure(closure)))
+                },
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+                NodeKind::Error(ref message) => {
+                    Some(Err(MifulError::runtime_error(&format!("Cannot evaluate a syntax error: {}", message), &self.owned_text, n.span).with_code(codes::SYNTAX_ERROR)))
+                },
 
-                                "input" => {
-                                    //
-                                    // Prints (printable:1), reads line from stdin, and returns it as (obj string)
+                NodeKind::LambdaHook(_) => {
+                    Some(self.eval_hook(&n))
+                },// [NOTE] Outside of a quote, the expression is just evaluated.
+            }
+        }
+    }
+}
 
-                                    if args.len() == 1 {
-                                        let result = self.print_fn(args[0].clone());
 
-                                        match result {
-                                            Ok(_v) => {
-                                                let read_line = input();
+#[cfg(test)]
+mod tests {
+    use super::Driver;
+    use console::Console;
+    use parsing::ast::{ NodeWrapper, MifulType };
+    use parsing::diagnostics::Renderer;
+    use parsing::utils::{ MifulError, Error, codes };
 
-                                                let line_result = Driver::over_input_line(&read_line).process();
+    use std::cell::RefCell;
+    use std::env;
+    use std::fs;
+    use std::io::{ self, Write };
+    use std::path::{ Path, PathBuf };
+    use std::process;
+    use std::rc::Rc;
 
-                                                match line_result {
-                                                    Ok(node_lst) => {
-                                                        Some(Ok(self.make_object("string".to_owned(), node_lst, n.index, n.position)))
-                                                    },
+    fn eval(src: &str) -> Vec<Result<NodeWrapper, MifulError>> {
+        let mut driver = Driver::new(src);
 
-                                                    Err(e) => {
-                                                        Some(Err(e))
-                                                    }
-                                                }
-                                            },
+        driver.process().unwrap();
+        driver.collect()
+    }
 
-                                            Err(e) => {
-                                                Some(Err(e))
-                                            },
-                                        }
+    fn eval_one(src: &str) -> String {
+        match eval(src).pop().unwrap() {
+            Ok(v) => { v.to_string() },
+            Err(e) => { panic!("{}", e); },
+        }
+    }
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+    fn eval_err(src: &str) -> MifulError {
+        match eval(src).pop().unwrap() {
+            Ok(v) => { panic!("Expected an error, got ` {} `", v); },
+            Err(e) => { e },
+        }
+    }
 
-                                "mk-sym" => {
-                                    //
-                                    // Creates a symbol from (word:1)
-                                    //
-                                    // [NOTE] Synonymous with `(word:1)`
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval_one("[+ 2 3]"), "5");
+        assert_eq!(eval_one("[- 2 3]"), "-1");
+        assert_eq!(eval_one("[* 4 -3]"), "-12");
+        assert_eq!(eval_one("[+ 1.5 2.25]"), "3.75");
+        assert_eq!(eval_one("[/ 7.0 2.0]"), "3.5");
+        assert_eq!(eval_one("[+ \"ab\" \"cd\"]"), "\"abcd\"");
 
-                                    if args.len() == 1 {
-                                        if let NodeKind::Word(w) = &args[0].node {
-                                            Some(Ok(NodeWrapper::new_symbol(w.to_owned(), n.index, n.position)))
+        // [NOTE] Ints and floats don't mix, the error points at the operand of the other kind.
+        //
+        for op in &["+", "-", "*", "/", "%"] {
+            let e = eval_err(&format!("[{} 1 2.0]", op));
 
-                                        } else if let NodeKind::Int(i) = &args[0].node {
-                                            Some(Ok(NodeWrapper::new_symbol(i.to_string(), n.index, n.position)))
+            assert_eq!(e.get_code(), codes::PARAM_TYPE, "{}", op);
+            assert_eq!(e.get_span().start_pos, (1, 6), "{}", op);
 
-                                        } else if let NodeKind::Float(f) = &args[0].node {
-                                            Some(Ok(NodeWrapper::new_symbol(f.to_string(), n.index, n.position)))
+            let e = eval_err(&format!("[{} 2.0 1]", op));
 
-                                        } else {
-                                            Some(Err(self.param_type("word", args[0].index, args[0].position)))
-                                        }// [ERR] Parameter type
+            assert_eq!(e.get_code(), codes::PARAM_TYPE, "{}", op);
+            assert_eq!(e.get_span().start_pos, (1, 8), "{}", op);
+        }
+    }
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+    #[test]
+    fn floor_semantics() {
+        assert_eq!(eval_one("[/ 7 2]"), "3");
+        assert_eq!(eval_one("[/ -7 2]"), "-4");
+        assert_eq!(eval_one("[/ 7 -2]"), "-4");
+        assert_eq!(eval_one("[/ -7 -2]"), "3");
+
+        assert_eq!(eval_one("[% 7 2]"), "1");
+        assert_eq!(eval_one("[% -7 2]"), "1");
+        assert_eq!(eval_one("[% 7 -2]"), "-1");
+        assert_eq!(eval_one("[% -7.5 2.0]"), "0.5");
+
+        assert_eq!(eval_one("[floor -1.5]"), "-2");
+        assert_eq!(eval_one("[ceil -1.5]"), "-1");
+        assert_eq!(eval_one("[round 2.5]"), "3");
+        assert_eq!(eval_one("[round -2.5]"), "-3");
+    }
 
-                                // "export" => {
-                                //     //
-                                // },
+    #[test]
+    fn division_by_zero() {
+        for src in &["[/ 1 0]", "[% 1 0]", "[/ 1.0 0.0]", "[% 1.0 0.0]"] {
+            let e = eval_err(src);
 
-                                ":" => {
-                                    //
-                                    // Returns the value in scope under the name of (word:1)
+            assert_eq!(e.get_code(), codes::DIVISION_BY_ZERO);
+            assert_eq!(e.get_span().start_pos, (1, src.rfind(' ').unwrap() + 2));
+        }
 
-                                    if args.len() == 1 {
-                                        if let NodeKind::Word(v) | NodeKind::Symbol(v) = &args[0].node {
-                                            if let Some(val) = self.scope.get(v) {
-                                                Some(Ok(val.clone()))
+        let e = eval_err("[/ -9223372036854775808 -1]");
 
-                                            } else {
-                                                Some(Err(MifulError::runtime_error("Undefined constant!", &self.owned_text, n.index, n.position)))
-                                            }// [ERR] Undefined constant
+        assert_eq!(e.get_code(), codes::INTEGER_OVERFLOW);
+    }
 
-                                        } else {
-                                            Some(Err(self.param_type("(word | symbol)", n.index, n.position)))
-                                        }// [ERR] Parameter type
+    #[test]
+    fn type_errors_blame_the_wrong_operand() {
+        for op in &["+", "-", "*", "/", "%"] {
+            let e = eval_err(&format!("[{} sword 1]", op));
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+            assert_eq!(e.get_code(), codes::PARAM_TYPE);
+            assert_eq!(e.get_span().start_pos, (1, 4));
 
-                                "=" => {
-                                    //
-                                    // Returns whether (val:1) and (val:2) are equal.
+            let e = eval_err(&format!("[{} 1 sword]", op));
 
-                                    if args.len() == 2 {
-                                        Some(self.values_equal(&args[0], &args[1]))
+            assert_eq!(e.get_code(), codes::PARAM_TYPE);
+            assert_eq!(e.get_span().start_pos, (1, 6));
+        }
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        let e = eval_err("[- 1 2.0]");
 
-                                // [TODO] Hooks
-                                //
-                                "+" => {
-                                    //
-                                    // Returns (int:1) + (int:2), (float:1) + (float:2),
-                                    // or is synonymous with [obj-append [:obj1] [obj-unwrap [:obj2]]],
-                                    // where (obj:1) and (obj:2) are of the same type.
+        assert_eq!(e.get_span().start_pos, (1, 6));
 
-                                    if args.len() == 2 {
-                                        let a = &args[0];
-                                        let b = &args[1];
+        let e = eval_err("[floor sword]");
 
-                                        match (&a.node, &b.node) {
-                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
-                                                Some(Ok(NodeWrapper::new_int(i1 + i2, n.index, n.position)))
-                                            },
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+    }
+
+    #[test]
+    fn unquote_in_quote() {
+        assert_eq!(eval_one("[let x 5 {let q {+ 1 {? [: x] ?}} {call [: q]}}]"), "6");
+        assert_eq!(eval_one("{? [+ 1 2] ?}"), "3");
+    }
+
+    #[test]
+    fn unquotes_keep_their_order() {
+        assert_eq!(eval_one("[let x 5 {call {- {? [* 2 [: x]] ?} {? [: x] ?}}}]"), "5");
+        assert_eq!(eval_one("[let x 5 {call {- {? [: x] ?} {? [* 2 [: x]] ?}}}]"), "-5");
+    }
+
+    #[test]
+    fn unquotes_see_the_scope_of_creation() {
+        assert_eq!(eval_one("[let x 1 {let q {return {? [: x] ?}} {let x 2 {call [: q]}}}]"), "1");
+    }
+
+    #[test]
+    fn nested_unquotes() {
+        // [NOTE] The inner quote gets created (and its unquote resolved) when the outer one runs.
+        //
+        assert_eq!(eval_one("[let x 5 {let q {return {+ 1 {? [: x] ?}}} {call [call [: q]]}}]"), "6");
+
+        // [NOTE] An unquote inside the value of an unquote belongs to the quote created there.
+        //
+        assert_eq!(eval_one("[let x 1 {call {+ 1 {? [call {+ 1 {? [: x] ?}}] ?}}}]"), "3");
+        assert_eq!(eval_one("[let x 1 {call {+ {? [: x] ?} {? [call {+ 1 {? [: x] ?}}] ?}}}]"), "3");
+    }
+
+    #[test]
+    fn unquotes_survive_tail_and_reverse() {
+        assert_eq!(eval_one("[let x 5 {call {tail (1 {? [: x] ?} {? [* 2 [: x]] ?})}}]"), "(5 10)");
+        assert_eq!(eval_one("[let x 5 {call {reverse (1 {? [: x] ?} {? [* 2 [: x]] ?})}}]"), "(10 5 1)");
+        assert_eq!(eval_one("[let x 5 {call {reverse [tail (1 {? [: x] ?} {? [* 2 [: x]] ?} 3)]}}]"), "(3 10 5)");
+        assert_eq!(eval_one("[let x 5 {call {tail [reverse ({? [: x] ?} ({? [* 2 [: x]] ?} 7) 1)]}}]"), "((10 7) 5)");
+
+        // [NOTE] A quote keeps its own unquotes in order, wherever the list moves it.
+        //
+        assert_eq!(eval_one("[let x 5 {call {call [head [tail (0 {+ 1 {? [: x] ?}})]]}}]"), "6");
+        assert_eq!(eval_one("[let x 5 {call {call [head [tail [reverse (0 {- {? [: x] ?} {? [* 2 [: x]] ?}} 1)]]]}}]"), "-5");
+    }
+
+    // [NOTE] A fresh directory with the given files, for import tests.
+    //
+    fn module_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("miful-{}-{}", name, process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        for (file, source) in files {
+            fs::write(dir.join(file), source).unwrap();
+        }
+
+        dir
+    }
+
+    fn run_in(dir: &Path, main: &str, search: Option<PathBuf>) -> (Vec<String>, String) {
+        let pane = Pane::default();
+        let mut driver = Driver::with_console(main, Console::new(Box::new(pane.clone()), Box::new("".as_bytes())));
+
+        driver.set_path(dir.join("main.mf"));
+
+        if let Some(search) = search {
+            driver.add_search_path(search);
+        }
+
+        driver.process().unwrap();
+
+        let results = driver
+            .map(|r| r.map(|v| v.to_string()).unwrap_or_else(|e| e.get_code().to_owned()))
+            .collect();
+
+        let printed = String::from_utf8(pane.pending.borrow().clone()).unwrap();
+
+        (results, printed)
+    }
+
+    const NIL_TEXT: &str = "(_obj nil ())";
+
+    const ITEMS: &str = "[print \"loaded \"]\n[export give]\n[define give ((x str)) {secret [: x]}]\n[define secret ((x str)) {+ [: x] [: x]}]\n";
+
+    #[test]
+    fn imports_are_namespaced() {
+        let dir = module_dir("namespaced", &[("items.mf", ITEMS)]);
+
+        let (results, _) = run_in(&dir, "[import \"items\"]\n[items/give \"a\"]\n[import \"items\" it]\n[it/give \"b\"]\n[defined? give]", None);
+
+        assert_eq!(results[1 ..], ["\"aa\"", NIL_TEXT, "\"bb\"", "false"]);
+    }
+
+    #[test]
+    fn requires_use_plain_names() {
+        let dir = module_dir("required", &[("items.mf", ITEMS)]);
+
+        let (results, _) = run_in(&dir, "[require \"items\"]\n[give \"a\"]\n[defined? items/give]", None);
+
+        assert_eq!(results[1 ..], ["\"aa\"", "false"]);
+    }
+
+    #[test]
+    fn only_exports_are_defined() {
+        let dir = module_dir("exports", &[("items.mf", ITEMS)]);
+
+        let (results, _) = run_in(&dir, "[import \"items\"]\n[defined? items/secret]\n[items/secret \"a\"]\n[require \"items\"]\n[secret \"a\"]", None);
+
+        assert_eq!(results[1 ..], ["false", codes::NO_MATCHING_FUNCTION, NIL_TEXT, codes::NO_MATCHING_FUNCTION]);
+    }
+
+    #[test]
+    fn modules_run_once() {
+        let dir = module_dir("once", &[
+            ("items.mf", ITEMS),
+            ("shop.mf", "[require \"items\"]\n[define sell ((x str)) {give [: x]}]"),
+        ]);
+
+        let (results, printed) = run_in(&dir, "[import \"items\"]\n[import \"shop\"]\n[require \"items\"]\n[shop/sell \"c\"]", None);
+
+        assert_eq!(results[3], "\"cc\"");
+        assert_eq!(printed, "loaded ");
+    }
+
+    #[test]
+    fn search_path_fallback() {
+        let dir = module_dir("search-main", &[]);
+        let lib = module_dir("search-lib", &[("items.mf", ITEMS)]);
+
+        let (results, _) = run_in(&dir, "[import \"items\"]", None);
+
+        assert_eq!(results, [codes::MODULE_NOT_FOUND]);
+
+        let (results, printed) = run_in(&dir, "[import \"items\"]\n[items/give \"a\"]", Some(lib.clone()));
+
+        assert_eq!(results[1], "\"aa\"");
+        assert_eq!(printed, "loaded ");
+
+        // [NOTE] A file next to the importing one comes first.
+        //
+        fs::write(dir.join("items.mf"), "[define give ((x str)) {: x}]").unwrap();
+
+        let (results, _) = run_in(&dir, "[import \"items\"]\n[items/give \"a\"]", Some(lib));
+
+        assert_eq!(results[1], "\"a\"");
+    }
+
+    #[test]
+    fn module_errors_use_the_module_source() {
+        let dir = module_dir("source", &[
+            ("items.mf", "[define ok ((x int)) {+ [: x] 1}]\n\n[define boom ((x int)) {/ [: x] 0}]\n"),
+        ]);
+
+        let main = "[import \"items\"]\n[items/ok 1]\n[items/boom 1]\n";
+        let mut driver = Driver::new(main);
+
+        driver.set_path(dir.join("main.mf"));
+        driver.process().unwrap();
+
+        let e = driver.last().unwrap().unwrap_err();
+        let rendered = Renderer::plain().render(&e);
+
+        assert_eq!(e.get_code(), codes::DIVISION_BY_ZERO);
+        assert!(rendered.contains("3 | [define boom ((x int)) {/ [: x] 0}]"), "{}", rendered);
+        assert!(!rendered.contains("[items/ok 1]"), "{}", rendered);
+    }
+
+    #[test]
+    fn importing_the_entry_file_is_a_cycle() {
+        let dir = module_dir("cycle", &[
+            ("a.mf", "[import \"b\"]"),
+            ("b.mf", "[import \"a\"]"),
+        ]);
+
+        let main = fs::read_to_string(dir.join("a.mf")).unwrap();
+        let mut driver = Driver::new(&main);
+
+        driver.set_path(dir.join("a.mf"));
+        driver.process().unwrap();
+
+        let e = driver.last().unwrap().unwrap_err();
+
+        assert_eq!(e.get_code(), codes::IMPORT_CYCLE);
+        assert!(e.get_description().contains("a.mf -> "), "{}", e.get_description());
+    }
+
+    #[test]
+    fn ambiguous_overloads_are_rejected_on_every_path() {
+        let int_any = vec![MifulType::Simple("int".to_owned()), MifulType::Simple("any".to_owned())];
+        let any_int = vec![MifulType::Simple("any".to_owned()), MifulType::Simple("int".to_owned())];
+
+        let mut driver = Driver::new("[define f ((x any) (y int)) {: x}]");
+
+        driver.process().unwrap();
+        driver.by_ref().for_each(|r| { r.unwrap(); });
+
+        let e = driver.register_fn("f", int_any.clone(), |args| Ok(args[0].clone())).unwrap_err();
+
+        assert_eq!(e.get_code(), codes::AMBIGUOUS_OVERLOAD);
+
+        let mut driver = Driver::new("");
+
+        driver.register_fn("f", int_any, |args| Ok(args[0].clone())).unwrap();
+
+        let e = driver.register_fn("f", any_int, |args| Ok(args[0].clone())).unwrap_err();
+
+        assert_eq!(e.get_code(), codes::AMBIGUOUS_OVERLOAD);
+
+        let dir = module_dir("ambiguous", &[
+            ("items.mf", "[define f ((x int) (y any)) {: x}]"),
+        ]);
+
+        let main = "[define f ((x any) (y int)) {: y}]\n[require \"items\"]\n";
+        let mut driver = Driver::new(main);
+
+        driver.set_path(dir.join("main.mf"));
+        driver.process().unwrap();
+
+        let e = driver.last().unwrap().unwrap_err();
+
+        assert_eq!(e.get_code(), codes::AMBIGUOUS_OVERLOAD);
+        assert_eq!(e.get_span().start_pos, (2, 1));
+    }
+
+    #[test]
+    fn list_frames_point_at_the_list() {
+        let e = eval_err("[= ({+ 1 2}) ({+ 1 2})]");
+
+        assert_eq!(e.get_context()[0].span.start_pos, (1, 4));
+
+        let e = eval_err("[< (1 a) (1 \"b\")]");
+
+        assert_eq!(e.get_span().start_pos, (1, 13));
+        assert_eq!(e.get_context()[0].span.start_pos, (1, 4));
+    }
+
+    #[test]
+    fn defined_predicate() {
+        assert_eq!(eval_one("[define f ((x int)) {: x}]\n[defined? f]"), "true");
+        assert_eq!(eval_one("[defined? f]"), "false");
+        assert_eq!(eval_one("[define f ((x int)) {: x}]\n[undefine f]\n[defined? f]"), "false");
+    }
+
+    #[test]
+    fn range_limits() {
+        assert_eq!(eval_one("[range 2 5]"), "(2 3 4)");
+        assert_eq!(eval_one("[range 5 2]"), "()");
+
+        let e = eval_err("[range 0 9223372036854775807]");
+
+        assert_eq!(e.get_code(), codes::RANGE_TOO_LARGE);
+        assert_eq!(e.get_span().start_pos, (1, 10));
+
+        let e = eval_err("[range 0 sword]");
+
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+        assert_eq!(e.get_span().start_pos, (1, 10));
+
+        let e = eval_err("[range sword 1]");
+
+        assert_eq!(e.get_span().start_pos, (1, 8));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(eval_one("[< 1 2]"), "true");
+        assert_eq!(eval_one("[< 2 1]"), "false");
+        assert_eq!(eval_one("[<= 2 2]"), "true");
+        assert_eq!(eval_one("[<= 2.5 2.0]"), "false");
+        assert_eq!(eval_one("[> \"b\" \"a\"]"), "true");
+        assert_eq!(eval_one("[>= a `b`]"), "false");
+        assert_eq!(eval_one("[< (1 2) (1 3)]"), "true");
+        assert_eq!(eval_one("[<= (1 2) (1)]"), "false");
+
+        let e = eval_err("[< 1 2.0]");
+
+        assert_eq!(e.get_code(), codes::INCOMPARABLE);
+        assert_eq!(e.get_span().start_pos, (1, 6));
+
+        let e = eval_err("[<= a 1]");
+
+        assert_eq!(e.get_code(), codes::INCOMPARABLE);
+    }
+
+    #[test]
+    fn boolean_logic() {
+        assert_eq!(eval_one("[and `true` `true` `true`]"), "true");
+        assert_eq!(eval_one("[and `true` `false`]"), "false");
+        assert_eq!(eval_one("[or `false` `true`]"), "true");
+        assert_eq!(eval_one("[or `false` `false`]"), "false");
+        assert_eq!(eval_one("[not `true`]"), "false");
+        assert_eq!(eval_one("[not [< 2 1]]"), "true");
+
+        // [NOTE] Evaluation stops as soon as the result is known.
+        //
+        assert_eq!(eval_one("[and `false` [/ 1 0]]"), "false");
+        assert_eq!(eval_one("[or `true` [/ 1 0]]"), "true");
+
+        for src in &["[and `true` 1]", "[or `false` true]", "[not 1]"] {
+            let e = eval_err(src);
+
+            assert_eq!(e.get_code(), codes::PARAM_TYPE, "{}", src);
+        }
+
+        let e = eval_err("[and `true` 1]");
+
+        assert_eq!(e.get_span().start_pos, (1, 13));
+        assert_eq!(eval_err("[and `true`]").get_code(), codes::PARAM_COUNT);
+    }
+
+    #[test]
+    fn let_bindings() {
+        assert_eq!(eval_one("[let x 1 {+ [: x] 1}]"), "2");
+        assert_eq!(eval_one("[let x 1 {let y 2 {+ [: x] [: y]}}]"), "3");
+        assert_eq!(eval_one("[let x 1 {let x 2 {: x}}]"), "2");
+        assert_eq!(eval_one("[let* () {+ 1 2}]"), "3");
+        assert_eq!(eval_one("[let* ((x 1) (y [+ [: x] 1])) {: y}]"), "2");
+        assert_eq!(eval_one("[let* ((x 1) (x [+ [: x] 1])) {: x}]"), "2");
+        assert_eq!(eval_one("[let* ((x 1)) {let x 5 {: x}}]"), "5");
+        assert_eq!(eval_one("[let x 1 {let* ((y [: x]) (x 3)) {+ [: x] [: y]}}]"), "4");
+
+        // [NOTE] Bindings don't outlive their quote.
+        //
+        let results = eval("[let x 1 {: x}]\n[: x]");
+
+        assert_eq!(results[0].as_ref().unwrap().to_string(), "1");
+        assert_eq!(results[1].as_ref().unwrap_err().get_code(), codes::UNDEFINED_CONSTANT);
+    }
+
+    #[test]
+    fn malformed_let_bindings() {
+        let e = eval_err("[let 1 1 {: x}]");
+
+        assert_eq!(e.get_code(), codes::INVALID_BINDING);
+        assert_eq!(e.get_span().start_pos, (1, 6));
+
+        let e = eval_err("[let* (x 1) {: x}]");
+
+        assert_eq!(e.get_code(), codes::INVALID_BINDING);
+        assert_eq!(e.get_span().start_pos, (1, 8));
+
+        let e = eval_err("[let* ((x 1 2)) {: x}]");
+
+        assert_eq!(e.get_code(), codes::INVALID_BINDING);
+        assert_eq!(e.get_span().start_pos, (1, 8));
+
+        let e = eval_err("[let* ((1 2)) {: x}]");
+
+        assert_eq!(e.get_code(), codes::INVALID_BINDING);
+        assert_eq!(e.get_span().start_pos, (1, 9));
+
+        assert_eq!(eval_err("[let* 1 {: x}]").get_code(), codes::PARAM_TYPE);
+        assert_eq!(eval_err("[let x 1 {: y}]").get_code(), codes::UNDEFINED_CONSTANT);
+        assert_eq!(eval_err("[let x 1]").get_code(), codes::PARAM_COUNT);
+    }
+
+    const HERO: &str = "[struct hero ((name str) (hp int))]\n[define heal ((h (obj hero)) (n int)) {update [: h] hp [+ [@ [: h] hp] [: n]]}]\n";
+
+    fn with_hero(src: &str) -> String {
+        format!("{}{}", HERO, src)
+    }
+
+    #[test]
+    fn structures() {
+        assert_eq!(eval_one(&with_hero("[new hero (\"Ann\" 10)]")), "(_obj hero (\"Ann\" 10))");
+        assert_eq!(eval_one(&with_hero("[@ [new hero (\"Ann\" 10)] name]")), "\"Ann\"");
+        assert_eq!(eval_one(&with_hero("[@ [update [new hero (\"Ann\" 10)] hp 3] hp]")), "3");
+
+        // [NOTE] Associated functions come with the object as their first argument.
+        //
+        assert_eq!(eval_one(&with_hero("[@ [call [@ [new hero (\"Ann\" 10)] heal] 5] hp]")), "15");
+        assert_eq!(eval_one(&with_hero("[@ [heal [new hero (\"Ann\" 10)] 5] hp]")), "15");
+    }
+
+    #[test]
+    fn structure_errors() {
+        let e = eval_err(&with_hero("[new hero (\"Ann\" ten)]"));
+
+        assert_eq!(e.get_code(), codes::FIELD_TYPE);
+        assert_eq!(e.get_span().start_pos, (3, 18));
+
+        let e = eval_err(&with_hero("[update [new hero (\"Ann\" 10)] hp x]"));
+
+        assert_eq!(e.get_code(), codes::FIELD_TYPE);
+
+        let e = eval_err(&with_hero("[new hero (\"Ann\")]"));
+
+        assert_eq!(e.get_code(), codes::FIELD_COUNT);
+
+        let e = eval_err(&with_hero("[@ [new hero (\"Ann\" 10)] mp]"));
+
+        assert_eq!(e.get_code(), codes::UNKNOWN_FIELD);
+        assert!(e.get_description().contains("` mp `"), "{}", e.get_description());
+
+        let e = eval_err(&with_hero("[update [new hero (\"Ann\" 10)] mp 3]"));
+
+        assert_eq!(e.get_code(), codes::UNKNOWN_FIELD);
+
+        assert_eq!(eval_err("[new villain (1)]").get_code(), codes::UNDECLARED_STRUCTURE);
+        assert_eq!(eval_err("[struct p ((x int) (x int))]").get_code(), codes::DUPLICATE_FIELD);
+        assert_eq!(eval_err("[@ 1 hp]").get_code(), codes::INVALID_OBJECT);
+    }
+
+    #[test]
+    fn closures_capture_their_environment() {
+        assert_eq!(eval_one("[call [let x 10 {lambda ((y int)) {+ [: x] [: y]}}] 5]"), "15");
+        assert_eq!(eval_one("[apply [let x 10 {lambda ((y int)) {+ [: x] [: y]}}] (5)]"), "15");
+        assert_eq!(eval_one("[call [let x 1 {return {+ [: x]}}] 5]"), "6");
+        assert_eq!(eval_one("[map (1 2) [let k 10 {lambda ((v int)) {* [: k] [: v]}}]]"), "(10 20)");
+
+        // [NOTE] A binding at the call doesn't reach into the closure.
+        //
+        let adder = "[define adder ((n int)) {lambda ((y int)) {+ [: n] [: y]}}]\n";
+
+        assert_eq!(eval_one(&format!("{}[call [adder 3] 4]", adder)), "7");
+        assert_eq!(eval_one(&format!("{}[let n 100 {{call [adder 3] 4}}]", adder)), "7");
+        assert_eq!(eval_one("[define mk () {let x 7 {lambda () {: x}}}]\n[call [mk]]"), "7");
+    }
+
+    #[test]
+    fn apply_and_call() {
+        assert_eq!(eval_one("[apply [lambda ((a int) (b int)) {- [: a] [: b]}] (5 2)]"), "3");
+        assert_eq!(eval_one("[call [lambda ((a int) (b int)) {- [: a] [: b]}] 5 2]"), "3");
+        assert_eq!(eval_one("[apply {+ 1} (2)]"), "3");
+
+        let e = eval_err("[call [lambda ((a int)) {: a}] x]");
+
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+        assert_eq!(e.get_span().start_pos, (1, 32));
+
+        assert_eq!(eval_err("[call [lambda ((a int)) {: a}] 1 2]").get_code(), codes::PARAM_COUNT);
+        assert_eq!(eval_err("[call 1 2]").get_code(), codes::PARAM_TYPE);
+    }
 
-                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
-                                                Some(Ok(NodeWrapper::new_float(f1 + f2, n.index, n.position)))
-                                            },
+    #[test]
+    fn concat_of_nothing_is_a_list() {
+        assert_eq!(eval_one("[concat ((1) (2 3))]"), "(1 2 3)");
+        assert_eq!(eval_one("[concat (\"ab\" \"c\")]"), "\"abc\"");
+        assert_eq!(eval_one("[concat ()]"), "()");
+    }
 
-                                            (NodeKind::List(l1), NodeKind::List(l2)) => {
-                                                if self.check_obj_type(&a.node, "any")
-                                                    && self.check_obj_type(&b.node, "any") {
+    #[test]
+    fn diagnose_sorts_lexer_and_parser_errors() {
+        let mut driver = Driver::new("[f 1 } \"abc");
+        let (_, errors) = driver.diagnose();
 
-                                                    let t1 = self.get_obj_type(&l1[1]).unwrap();
-                                                    let t2 = self.get_obj_type(&l2[1]).unwrap();
+        let codes: Vec<&str> = errors.iter().map(|e| e.get_code()).collect();
 
-                                                    if t1 == t2 {
-                                                        Some(Ok(self.obj_append(a, l2.to_vec()).unwrap()))
+        assert_eq!(codes, vec![codes::UNTERMINATED_STRUCTURE, codes::UNEXPECTED_CONTROL, codes::UNTERMINATED_STRING]);
+    }
 
-                                                    } else {
-                                                        Some(Err(MifulError::runtime_error("Can't concat two different objects!", &self.owned_text, n.index, n.position)))
-                                                    }// [ERR] Different objects concat
+    #[test]
+    fn let_star_keeps_functions_defined_while_binding() {
+        assert_eq!(eval_one("[let* ((a [define f ((x int)) {* [: x] 2}])) {f 3}]"), "6");
+        assert_eq!(eval_one("[let* ((a [define f ((x int)) {* [: x] 2}]) (b [f 3])) {+ [: b] [f 1]}]"), "8");
+        assert_eq!(eval_one("[let* ((a 1) (b {+ [: a] 1})) {call [: b]}]"), "2");
+    }
 
-                                                } else {
-                                                    let mut new_l = l1.clone();
-                                                    let mut new_hooks = a.hooks.clone();
+    #[test]
+    fn runtime_errors_have_codes() {
+        let cases = [
+            ("[update [new p (1)] a 2]", codes::UNDECLARED_STRUCTURE),
+            ("[struct p ((a int) (a int))]", codes::DUPLICATE_FIELD),
+            ("[struct p ((a int))]\n[new p (1 2)]", codes::FIELD_COUNT),
+            ("[struct p ((a int))]\n[new p (x)]", codes::FIELD_TYPE),
+            ("[struct p ((a int))]\n[update [new p (1)] a x]", codes::FIELD_TYPE),
+            ("[struct p ((a int))]\n[@ [new p (1)] b]", codes::UNKNOWN_FIELD),
+            ("[struct p ((a int))]\n[update [new p (1)] b 2]", codes::UNKNOWN_FIELD),
+            ("[nth (1 2) 2]", codes::INDEX_OUT_OF_RANGE),
+            ("[nth (1 2) -1]", codes::INDEX_OUT_OF_RANGE),
+            ("[format \"{} {}\" (1)]", codes::FORMAT_VALUES),
+            ("[format \"{}\" (1 2)]", codes::FORMAT_VALUES),
+            ("[to-int \"abc\"]", codes::INVALID_CONVERSION),
+            ("[floor 1e300]", codes::INVALID_CONVERSION),
+            ("[sort (1 a 2)]", codes::INCOMPARABLE),
+            ("[< 1 1.0]", codes::INCOMPARABLE),
+            ("[let* ((a)) {: a}]", codes::INVALID_BINDING),
+            ("[let* (a) {: a}]", codes::INVALID_BINDING),
+            ("[head ()]", codes::EMPTY_LIST),
+            ("[tail ()]", codes::EMPTY_LIST),
+        ];
+
+        for (src, code) in cases.iter() {
+            assert_eq!(eval_err(src).get_code(), *code, "{}", src);
+        }
 
-                                                    new_l.append(&mut l2.clone());
-                                                    new_hooks.append(&mut b.hooks.clone());
+        let e = eval_err("[< a 1]");
 
-                                                    Some(Ok(NodeWrapper::new_list(new_l, new_hooks, n.index, n.position)))
-                                                }
-                                            },
+        assert_eq!(e.get_description(), "Cannot compare ` word ` with ` int `!");
+    }
 
-                                            _ => {
-                                                Some(Err(self.param_type("(int | float | (obj any) | list)", b.index, b.position)))
-                                            }// [ERR] Parameter type
-                                        }
+    // [NOTE] Keeps what's written until it gets flushed, like a terminal would.
+    //
+    #[derive(Clone, Default)]
+    struct Pane {
+        pending: Rc<RefCell<Vec<u8>>>,
+        shown: Rc<RefCell<Vec<u8>>>,
+    }
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+    impl Pane {
+        fn shown(&self) -> String {
+            String::from_utf8(self.shown.borrow().clone()).unwrap()
+        }
+    }
 
-                                "-" => {
-                                    //
-                                    // Returns (int:1) - (int:2) or (float:1) - (float:2).
+    impl Write for Pane {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.pending.borrow_mut().extend_from_slice(buf);
 
-                                    if args.len() == 2 {
-                                        let a = &args[0];
-                                        let b = &args[1];
+            Ok(buf.len())
+        }
 
-                                        match (&a.node, &b.node) {
-                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
-                                                Some(Ok(NodeWrapper::new_int(i1 - i2, n.index, n.position)))
-                                            },
+        fn flush(&mut self) -> io::Result<()> {
+            self.shown.borrow_mut().append(&mut self.pending.borrow_mut());
 
-                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
-                                                Some(Ok(NodeWrapper::new_float(f1 - f2, n.index, n.position)))
-                                            },
+            Ok(())
+        }
+    }
 
-                                            _ => {
-                                                Some(Err(self.param_type("(int | float)", b.index, b.position)))
-                                            }// [ERR] Parameter type
-                                        }
+    fn console_results(src: &str, console: Console) -> Vec<Result<NodeWrapper, MifulError>> {
+        let mut driver = Driver::with_console(src, console);
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        driver.process().unwrap();
+        driver.collect()
+    }
 
-                                "*" => {
-                                    //
-                                    // Returns (int:1) * (int:2), (float:1) * (float:2).
+    #[test]
+    fn scripted_input() {
+        let pane = Pane::default();
+        let console = Console::new(Box::new(pane.clone()), Box::new("Alice\r\nBob\n".as_bytes()));
 
-                                    if args.len() == 2 {
-                                        let a = &args[0];
-                                        let b = &args[1];
+        let results = console_results("[input \"Name? \"]\n[input \"Name? \"]", console);
+        let names: Vec<String> = results.into_iter().map(|r| r.unwrap().to_string()).collect();
 
-                                        match (&a.node, &b.node) {
-                                            (NodeKind::Int(i1), NodeKind::Int(i2)) => {
-                                                Some(Ok(NodeWrapper::new_int(i1 * i2, n.index, n.position)))
-                                            },
+        assert_eq!(names, vec!["\"Alice\"", "\"Bob\""]);
+        assert_eq!(pane.shown(), "Name? Name? ");
+    }
 
-                                            (NodeKind::Float(f1), NodeKind::Float(f2)) => {
-                                                Some(Ok(NodeWrapper::new_float(f1 * f2, n.index, n.position)))
-                                            },
+    #[test]
+    fn input_from_a_function() {
+        let mut lines = vec!["2".to_owned(), "1".to_owned()];
+        let console = Console::with_input_fn(Box::new(Pane::default()), move || lines.pop());
 
-                                            _ => {
-                                                Some(Err(self.param_type("(int | float)", b.index, b.position)))
-                                            }// [ERR] Parameter type
-                                        }
+        let results = console_results("[+ [to-int [input \"\"]] [to-int [input \"\"]]]", console);
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        assert_eq!(results[0].as_ref().unwrap().to_string(), "3");
+    }
 
-                                "if" => {
-                                    //
-                                    // Runs (quote:2) when (value:1) is `true`, or runs (quote:3) otherwise.
+    #[test]
+    fn end_of_input() {
+        let console = Console::with_input_fn(Box::new(Pane::default()), || None);
 
-                                    if args.len() == 3 {
-                                        let cond_node = &args[0];
-                                        let true_node = &args[1];
-                                        let false_node = &args[2];
+        let e = console_results("[+ 1 2]\n  [input \"> \"]", console).pop().unwrap().unwrap_err();
 
-                                        if let NodeKind::Quote{ target: t_target, with: t_with } = &true_node.node {
-                                            if let NodeKind::Quote{ target: f_target, with: f_with } = &false_node.node {
-                                                if let NodeKind::Symbol(s) = &cond_node.node {
-                                                    if s == "true" {
-                                                        let t_invoke = NodeWrapper::new_invoke(t_target.to_string(),
-                                                            t_with.to_vec(), true_node.hooks.clone(), true_node.index, true_node.position);
+        assert_eq!(e.get_code(), codes::END_OF_INPUT);
+        assert_eq!(e.get_span().start_pos, (2, 3));
 
-                                                        let inner_driver = Driver::over(self.owned_text.clone(), vec![t_invoke], self.scope.clone(), self.functions.clone());
-                                                        let arg_result: Result<Vec<_>, _> = inner_driver.collect();
+        let console = Console::new(Box::new(Pane::default()), Box::new("".as_bytes()));
 
-                                                        match arg_result {
-                                                            Ok(ret) => {
-                                                                return Some(Ok(ret[0].clone()));
-                                                            },
+        let e = console_results("[input \"> \"]", console).pop().unwrap().unwrap_err();
 
-                                                            Err(e) => {
-                                                                let mut new_e = e;
+        assert_eq!(e.get_code(), codes::END_OF_INPUT);
+    }
 
-                                                                new_e.add_layer_top("..while evaluating parameters");
+    #[test]
+    fn error_output_and_flush() {
+        let pane = Pane::default();
+        let errors = Pane::default();
 
-                                                                return Some(Err(new_e));
-                                                            }// [ERR] While param eval
-                                                        }
-                                                    }
-                                                }
+        let console = Console::new(Box::new(pane.clone()), Box::new("".as_bytes()))
+            .error_output(Box::new(errors.clone()));
 
-                                                let f_invoke = NodeWrapper::new_invoke(f_target.to_string(),
-                                                    f_with.to_vec(), false_node.hooks.clone(), false_node.index, false_node.position);
+        let mut driver = Driver::with_console("[print \"a\"]\n[eprint \"b\"]\n[flush]\n[print \"c\"]", console);
 
-                                                let inner_driver = Driver::over(self.owned_text.clone(), vec![f_invoke], self.scope.clone(), self.functions.clone());
-                                                let arg_result: Result<Vec<_>, _> = inner_driver.collect();
+        driver.process().unwrap();
 
-                                                match arg_result {
-                                                    Ok(ret) => {
-                                                        return Some(Ok(ret[0].clone()));
-                                                    },
+        for _ in 0 .. 2 {
+            driver.next().unwrap().unwrap();
+        }
 
-                                                    Err(e) => {
-                                                        let mut new_e = e;
+        assert_eq!(pane.shown(), "");
+        assert_eq!(errors.shown(), "");
 
-                                                        new_e.add_layer_top("..while evaluating parameters");
+        driver.for_each(|r| { r.unwrap(); });
 
-                                                        return Some(Err(new_e));
-                                                    }// [ERR] While param eval
-                                                }
+        assert_eq!(pane.shown(), "a");
+        assert_eq!(errors.shown(), "b");
+        assert_eq!(*pane.pending.borrow(), b"c");
+    }
 
-                                            } else {
-                                                Some(Err(self.param_type("quote", true_node.index, false_node.position)))
-                                            }// [ERR] Parameter type
+    fn host_driver(src: &str) -> Driver<'_> {
+        let mut driver = Driver::new(src);
 
-                                        } else {
-                                            Some(Err(self.param_type("quote", true_node.index, true_node.position)))
-                                        }// [ERR] Parameter type
+        driver.register_fn("give-item", vec![MifulType::Simple("word".to_owned())], |args| {
+            if args[0].to_string() == "sword" {
+                Err("No swords left!".to_owned())
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+            } else {
+                Ok(NodeWrapper::new_list(vec![args[0].clone(), args[0].clone()], vec![], args[0].span))
+            }
+        }).unwrap();
 
-                                "return" => {
-                                    //
-                                    // Returns the given (value:1)
+        driver.process().unwrap();
 
-                                    if args.len() == 1 {
-                                        Some(Ok(args[0].clone()))
+        driver
+    }
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+    #[test]
+    fn host_functions_are_called() {
+        let results: Vec<String> = host_driver("[give-item apple]\n[map (a b) {give-item}]")
+            .map(|r| r.unwrap().to_string())
+            .collect();
 
-                                "define" => {
-                                    //
-                                    // Adds to the function scope a new function with the name (word:1),
-                                    // parameter signature ((tuple *(tuple (word, type))):2),
-                                    // and body (quote:3).
+        assert_eq!(results, vec!["(apple apple)", "((a a) (b b))"]);
+    }
 
-                                    if with.len() == 3 {
-                                        let raw_1 = &args[0];
-                                        let raw_2 = &args[1];
-                                        let raw_3 = &args[2];// [TODO] Substitute into locally defined functions
+    #[test]
+    fn host_function_errors() {
+        let e = host_driver("[+ 1 2]\n  [give-item sword]").last().unwrap().unwrap_err();
 
-                                        if let NodeKind::Word(def_name) | NodeKind::Symbol(def_name) = &raw_1.node {
-                                            if let NodeKind::List(raw_lst) = &raw_2.node {
-                                                if let NodeKind::Quote{ target, with: params } = &raw_3.node {
-                                                    let body_invoke = NodeWrapper::new_invoke(target.to_owned(), params.to_vec(), raw_3.hooks.clone(), raw_3.index, raw_3.position);
+        assert_eq!(e.get_code(), codes::HOST_FUNCTION);
+        assert_eq!(e.get_description(), "No swords left!");
+        assert_eq!(e.get_span().start_pos, (2, 3));
 
-                                                    Some(self.define_function(&def_name, raw_lst.to_vec(), body_invoke))
+        let e = host_driver("[give-item 1]").last().unwrap().unwrap_err();
 
-                                                } else {
-                                                    Some(Err(self.param_type("quote", raw_3.index, raw_3.position)))
-                                                }// [ERR] 3rd parameter type
+        assert_eq!(e.get_code(), codes::NO_MATCHING_FUNCTION);
+    }
 
-                                            } else {
-                                                Some(Err(self.param_type("(list *(list (word type)))", raw_2.index, raw_2.position)))
-                                            }// [ERR] 2nd parameter type
+    #[test]
+    fn host_functions_are_protected() {
+        for src in &["[define give-item ((x int)) {: x}]", "[undefine give-item]", "[undefine give-item (word)]"] {
+            let e = host_driver(src).last().unwrap().unwrap_err();
 
-                                        } else {
-                                            Some(Err(self.param_type("(word | symbol)", raw_1.index, raw_1.position)))
-                                        }// [ERR] 1st parameter type
+            assert_eq!(e.get_code(), codes::BUILTIN_FUNCTION, "{}", src);
+            assert_eq!(e.get_span().start_pos, (1, 1), "{}", src);
+        }
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(3, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        let results: Vec<String> = host_driver("[undefine give-item]\n[give-item apple]")
+            .map(|r| r.map(|v| v.to_string()).unwrap_or_else(|e| e.get_code().to_owned()))
+            .collect();
 
-                                "obj-append" => {
-                                    //
-                                    // Appends (list:2) to the contents of (obj:1)
+        assert_eq!(results[1], "(apple apple)");
+    }
 
-                                    if args.len() == 2 {
-                                        let obj = &args[0];
-                                        let lst_node = &args[1];
+    #[test]
+    fn list_builtins() {
+        assert_eq!(eval_one("[map (1 2 3) {* 2}]"), "(2 4 6)");
+        assert_eq!(eval_one("[filter (1 2 3 4) {> 3}]"), "(1 2)");
+        assert_eq!(eval_one("[fold (1 2 3) 10 {-}]"), "4");
+        assert_eq!(eval_one("[zip (1 2 3) (a b)]"), "((1 a) (2 b))");
+        assert_eq!(eval_one("[nth (a b c) 1]"), "b");
+        assert_eq!(eval_one("[take (1 2 3) 2]"), "(1 2)");
+        assert_eq!(eval_one("[take (1 2) 5]"), "(1 2)");
+        assert_eq!(eval_one("[drop (1 2 3) 1]"), "(2 3)");
+        assert_eq!(eval_one("[drop (1 2) 5]"), "()");
+        assert_eq!(eval_one("[contains (1 (2 3)) (2 3)]"), "true");
+        assert_eq!(eval_one("[contains (1 2) a]"), "false");
+    }
 
-                                        if let NodeKind::List(lst) = &lst_node.node {
-                                            Some(self.obj_append(obj, lst.to_vec()))
+    #[test]
+    fn list_builtin_errors() {
+        let e = eval_err("[map (1 a) {* 2}]");
 
-                                        } else {
-                                            Some(Err(self.param_type("list", lst_node.index, lst_node.position)))
-                                        }// [ERR] Parameter type
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+        assert_eq!(e.get_span().start_pos, (1, 9));
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(2, with.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        let e = eval_err("[filter (1 2) {+ 1}]");
 
-                                "length" => {
-                                    //
-                                    // Returns (int), which is the length of (tuple:1)
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
 
-                                    if args.len() == 1 {
-                                        let lst_node = &args[0];
+        let e = eval_err("[fold (1 a) 0 {+}]");
 
-                                        if let NodeKind::List(lst) = &lst_node.node {
-                                            Some(Ok(NodeWrapper::new_int(lst.len() as i64, n.index, n.position)))
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+        assert_eq!(e.get_span().start_pos, (1, 10));
 
-                                        } else {
-                                            Some(Err(self.param_type("(list (any))", lst_node.index, lst_node.position)))
-                                        }// [ERR] Parameter type
+        let e = eval_err("[zip 1 (a)]");
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+        assert_eq!(e.get_span().start_pos, (1, 6));
 
-                                "head" => {
-                                    //
-                                    // Returns the first element of (list:1)
+        let e = eval_err("[nth (a b) 2]");
 
-                                    if args.len() == 1 {
-                                        let lst_node = &args[0];
+        assert_eq!(e.get_code(), codes::INDEX_OUT_OF_RANGE);
+        assert_eq!(e.get_span().start_pos, (1, 12));
 
-                                        if let NodeKind::List(lst) = &lst_node.node {
-                                            if lst.len() > 0 {
-                                                Some(Ok(lst[0].clone()))
+        for src in &["[nth (a b) -1]", "[take (a b) -1]", "[drop (a b) -1]"] {
+            let e = eval_err(src);
 
-                                            } else {
-                                                Some(Err(MifulError::runtime_error("Cannot get head of empty tuple!", &self.owned_text, lst_node.index, lst_node.position)))
-                                            }// [ERR] Head of empty tuple
+            assert_eq!(e.get_code(), codes::INDEX_OUT_OF_RANGE, "{}", src);
+        }
+    }
 
-                                        } else {
-                                            Some(Err(self.param_type("(list (any))", lst_node.index, lst_node.position)))
-                                        }// [ERR] Parameter type
+    #[test]
+    fn sorting() {
+        assert_eq!(eval_one("[sort ()]"), "()");
+        assert_eq!(eval_one("[sort (5 3 9 1 3 7 2)]"), "(1 2 3 3 5 7 9)");
+        assert_eq!(eval_one("[sort (1.5 -2.0 0.5)]"), "(-2 0.5 1.5)");
+        assert_eq!(eval_one("[sort (b `a` c)]"), "(a b c)");
+        assert_eq!(eval_one("[sort ((2 a) (1 b) (1 a) (1))]"), "((1) (1 a) (1 b) (2 a))");
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        // [NOTE] The error comes out wherever the incomparable pair is.
+        //
+        for src in &["[sort (1 a)]", "[sort (3 2 1 a)]", "[sort (a 3 2 1)]", "[sort ((1 2) (1 b) (0))]", "[sort (b \"x\")]"] {
+            let e = eval_err(src);
 
-                                // [TODO] Resolve hooks (distribution between head and tail + indexing)
-                                //
-                                "tail" => {
-                                    //
-                                    // Returns the (list:1) without head.
+            assert_eq!(e.get_code(), codes::INCOMPARABLE, "{}", src);
+            assert_eq!(e.get_context()[0].span.start_pos, (1, 1), "{}", src);
+        }
+    }
 
-                                    if args.len() == 1 {
-                                        let lst_node = &args[0];
+    #[test]
+    fn string_splitting() {
+        assert_eq!(eval_one("[split \"a,b,,c\" \",\"]"), "(\"a\" \"b\" \"\" \"c\")");
+        assert_eq!(eval_one("[split \"ae\u{301}👍🏽\" \"\"]"), "(\"a\" \"e\u{301}\" \"👍🏽\")");
 
-                                        if let NodeKind::List(lst) = &lst_node.node {
-                                            if lst.len() > 0 {
-                                                let (_, tail) = lst.split_first().unwrap();
+        assert_eq!(eval_one("[substring \"ae\u{301}👍🏽z\" 1 3]"), "\"e\u{301}👍🏽\"");
+        assert_eq!(eval_one("[substring \"abc\" 1 100]"), "\"bc\"");
+        assert_eq!(eval_one("[substring \"abc\" 5 9]"), "\"\"");
 
-                                                Some(Ok(NodeWrapper::new_list(tail.to_vec(), lst_node.hooks.clone(), n.index, n.position)))
+        assert_eq!(eval_one("[split_at \"👍🏽ab\" 1]"), "(\"👍🏽\" \"ab\")");
+        assert_eq!(eval_one("[split_at \"ab\" 7]"), "(\"ab\" \"\")");
 
-                                            } else {
-                                                Some(Err(MifulError::runtime_error("Cannot get tail of empty tuple!", &self.owned_text, lst_node.index, lst_node.position)))
-                                            }// [ERR] Head of empty tuple
+        let e = eval_err("[substring \"abc\" -1 2]");
 
-                                        } else {
-                                            Some(Err(self.param_type("(list (any))", lst_node.index, lst_node.position)))
-                                        }// [ERR] Parameter type
+        assert_eq!(e.get_code(), codes::INDEX_OUT_OF_RANGE);
+        assert_eq!(e.get_span().start_pos, (1, 18));
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        let e = eval_err("[split_at \"abc\" -2]");
 
-                                "reverse" => {
-                                    //
-                                    // Returns the (list:1), reversed.
+        assert_eq!(e.get_code(), codes::INDEX_OUT_OF_RANGE);
+        assert_eq!(e.get_span().start_pos, (1, 17));
 
-                                    if args.len() == 1 {
-                                        let lst_node = &args[0];
+        let e = eval_err("[split 1 \",\"]");
 
-                                        if let NodeKind::List(lst) = &lst_node.node {
-                                            let mut mut_lst = lst.clone();
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+        assert_eq!(e.get_span().start_pos, (1, 8));
+    }
 
-                                            mut_lst.reverse();
+    #[test]
+    fn string_case_and_format() {
+        assert_eq!(eval_one("[to-upper \"straße é\"]"), "\"STRASSE É\"");
+        assert_eq!(eval_one("[to-lower \"ÀB c\"]"), "\"àb c\"");
+        assert_eq!(eval_one("[trim \"\t a b \n\"]"), "\"a b\"");
 
-                                            Some(Ok(NodeWrapper::new_list(mut_lst, lst_node.hooks.clone(), n.index, n.position)))
+        assert_eq!(eval_one("[format \"{} + {} = {{{}}}\" (1 2.5 three)]"), "\"1 + 2.5 = {three}\"");
+        assert_eq!(eval_one("[format \"[{}]\" (\"s\")]"), "\"[s]\"");
 
-                                        } else {
-                                            Some(Err(self.param_type("(list (any))", lst_node.index, lst_node.position)))
-                                        }// [ERR] Parameter type
+        let e = eval_err("[format \"{} {}\" (1)]");
 
-                                    } else {
-                                        Some(Err(self.invalid_param_count(1, args.len(), n)))
-                                    }// [ERR] Parameter count
-                                },
+        assert_eq!(e.get_code(), codes::FORMAT_VALUES);
 
-                                f_name => {
-                                    Some(self.call_function(f_name, args, &n))
-                                },
-                            }
-                        },
+        let e = eval_err("[format \"{}\" (1 2)]");
 
-                        Err(e) => {
-                            Some(Err(self.param_eval(e)))
-                        }// [ERR] Param eval
-                    }
-                },
+        assert_eq!(e.get_code(), codes::FORMAT_VALUES);
 
-                NodeKind::Quote{ target, with } => {
-                    let mut new_with = vec![];
+        let e = eval_err("[to-upper word]");
 
-                    for arg in with {
-                        let new_arg = self.resolve_hooks(arg, &hooks);
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+        assert_eq!(e.get_span().start_pos, (1, 11));
+    }
 
-                        new_with.push(new_arg);
-                    }
+    #[test]
+    fn string_conversions() {
+        assert_eq!(eval_one("[to-str 12]"), "\"12\"");
+        assert_eq!(eval_one("[to-str \"ab\"]"), "\"ab\"");
+        assert_eq!(eval_one("[to-str (1 a)]"), "\"(1 a)\"");
 
-                    Some(Ok(NodeWrapper::new_quote(target, new_with, vec![], n.index, n.position)))
-                },// [TODO] Inline local function calls
+        assert_eq!(eval_one("[to-int \"42\"]"), "42");
+        assert_eq!(eval_one("[to-int \" -7 \"]"), "-7");
+        assert_eq!(eval_one("[to-int \"0x10\"]"), "16");
+        assert_eq!(eval_one("[to-int \"1_000\"]"), "1000");
+        assert_eq!(eval_one("[to-float \"2.5e1\"]"), "25");
+        assert_eq!(eval_one("[to-float \"0b11\"]"), "3");
 
-                // NodeKind::LambdaHook(n) => {
-                //     //
-                // },
+        for src in &["[to-int \"1.5\"]", "[to-int \"abc\"]", "[to-float \"1__0\"]", "[to-int \"99999999999999999999\"]"] {
+            let e = eval_err(src);
 
-                _ => { None },// [FIXME] Add the rest.
-            }
+            assert_eq!(e.get_code(), codes::INVALID_CONVERSION, "{}", src);
+            assert_eq!(e.get_span().start_pos.1, src.find('"').unwrap() + 1, "{}", src);
         }
+
+        let e = eval_err("[to-int 12]");
+
+        assert_eq!(e.get_code(), codes::PARAM_TYPE);
+    }
+
+    #[test]
+    fn parameter_count() {
+        assert_eq!(eval_err("[- 1]").get_code(), codes::PARAM_COUNT);
+        assert_eq!(eval_err("[/ 1 2 3]").get_code(), codes::PARAM_COUNT);
+
+        assert_eq!(eval_err("[undefine]").get_description(), "Expected 1 or 2 parameters; got 0!");
+        assert_eq!(eval_err("[undefine f (int) x]").get_description(), "Expected 1 or 2 parameters; got 3!");
+        assert_eq!(eval_err("[import \"a\" b c]").get_description(), "Expected 1 or 2 parameters; got 3!");
+        assert_eq!(eval_err("[require \"a\" b]").get_description(), "Expected 1 parameters; got 2!");
     }
 }
@@ -0,0 +1,40 @@
+// [NOTE] Miful as a library. A host embeds the language by creating a `Driver`
+// over its source and iterating over the results:
+//
+//    let mut driver = miful::Driver::new("[+ 1 2]");
+//
+//    match driver.process() {
+//        Ok(_) => { for result in driver { ... } },
+//        Err(e) => { e.print_err(); },
+//    }
+//
+// A host with its own driver can use the `parsing` modules directly.
+//
+// [NOTE] Errors are passed on with explicit matches, usually adding context
+// on the way, so `?` isn't suggested where nothing gets added.
+//
+#![allow(clippy::question_mark)]
+
+// [NOTE] `Option::is_none_or` needs Rust 1.82, `map_or(true, ...)` builds on older ones.
+//
+#![allow(clippy::unnecessary_map_or)]
+
+extern crate unicode_segmentation;
+
+#[macro_use]
+pub mod parsing;
+pub mod driver;
+pub mod convert;
+pub mod console;
+pub mod repl;
+pub mod modules;
+
+pub use driver::{ Driver, HostFn };
+pub use console::Console;
+pub use repl::Repl;
+pub use convert::{ IntoMiful, FromMiful, Object, ObjectFields, is_nil };
+
+pub use parsing::ast::{ NodeWrapper, NodeKind, MifulType };
+pub use parsing::span::Span;
+pub use parsing::diagnostics::{ Renderer, Label };
+pub use parsing::utils::{ Error, MifulError, ParseError, SemanticError, RuntimeError, Frame, FrameKind, codes };
@@ -1,100 +1,191 @@
-#[macro_use]
-extern crate text_io;
-extern crate unicode_segmentation;
+extern crate miful;
 
-#[macro_use]
-mod parsing;
-mod driver;
+use miful::{ Driver, Repl, Error, MifulError, Renderer, is_nil };
 
-use parsing::utils::Error;
+use std::env;
+use std::fs;
+use std::io::{ self, Read };
+use std::path::PathBuf;
+use std::process;
 
 
-fn main() {
-    let test_long =
-    "[define
-        foo
-        ()
-        {if [> [first (1 2 3)] [last (1 2 3)]]
-            {println
-                [input [string (Enter [:space] a [:space] message: [:space])]]
-            }
-            {println
-                No
-            }
+/*
+
+# Miful CLI
+
+    miful
+    > starts the REPL (see `repl.rs`)
+
+    miful run (file) (args...)
+    > runs the script in (file), (args...) are returned by `args`
+
+    miful eval (expression)
+    > evaluates (expression) and prints the result of every top-level value,
+    except nil (like the REPL)
+
+    miful check (file)
+    > reports syntax errors and invalid signatures, without running anything
+
+    A (file) or (expression) of `-` is read from the standard input. Imports of
+`run` are looked up next to (file), then in the directories of `MIFUL_PATH`.
+
+## Exit codes
+
+* 0 - success
+* 1 - invalid usage, or the source can't be read
+* 2 - parse error (or invalid signature with `check`)
+* 3 - runtime error
+
+*/
+
+
+const EXIT_USAGE: i32 = 1;
+const EXIT_PARSE: i32 = 2;
+const EXIT_RUNTIME: i32 = 3;
+
+const USAGE: &str = "Usage:
+    miful
+    miful run <file> [args...]
+    miful eval <expression>
+    miful check <file>
+
+A <file> or <expression> of `-` is read from the standard input.";
+
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+
+    process::exit(EXIT_USAGE);
+}
+
+fn report(e: &MifulError) {
+    let _ = e.report(&mut io::stderr(), &Renderer::for_stderr());
+
+    eprintln!();
+}
+
+fn read_stdin() -> String {
+    let mut source = String::new();
+
+    if let Err(e) = io::stdin().read_to_string(&mut source) {
+        eprintln!("Cannot read the standard input: {}", e);
+
+        process::exit(EXIT_USAGE);
+    }
+
+    source
+}
+
+fn read_file(path: &str) -> String {
+    if path == "-" {
+        return read_stdin();
+    }
+
+    match fs::read_to_string(path) {
+        Ok(source) => { source },
+
+        Err(e) => {
+            eprintln!("Cannot read ` {} `: {}", path, e);
+
+            process::exit(EXIT_USAGE);
+        },
+    }
+}
+
+// [NOTE] Reports every syntax error at once before running anything.
+//
+fn parse(driver: &mut Driver) {
+    let (_, errors) = driver.diagnose();
+
+    if !errors.is_empty() {
+        for e in &errors {
+            report(e);
         }
-    ]";
 
-    let test = "
-    [define drop ((n int) (lst list))
-        {if [= [:n] 0]
-            {:lst}
-            {drop [- [:n] 1] [tail [:lst]]}
+        process::exit(EXIT_PARSE);
+    }
+}
+
+fn evaluate(driver: Driver, print_results: bool) {
+    for result in driver {
+        match result {
+            Ok(v) => {
+                if print_results && !is_nil(&v) {
+                    println!("{}", v);
+                }
+            },
+
+            Err(e) => {
+                report(&e);
+
+                process::exit(EXIT_RUNTIME);
+            },
         }
-    ]
+    }
+}
 
-    [define elem ((n int) (lst list)) {head [drop [:n] [:lst]]}]
-    [define obj-unwrap ((o (obj any))) {elem 2 [:o]}]
+fn run(path: &str, args: Vec<String>) {
+    let source = read_file(path);
+    let mut driver = Driver::new(&source);
 
-    [define string ((seq (list (word symbol)))) {return (`_obj` string [:seq])}]
-    [define string ((num (int | float))) {return (`_obj` string ([mk-sym [:num]]))}]
+    driver.set_args(args);
 
-    [define println ((val word)) {print [string ([:val] [:newline])]}]
-    [define println ((val symbol)) {print [string ([:val] [:newline])]}]
-    [define println ((val (obj string))) {print [obj-append [:val] ([:newline])]}]
+    if path != "-" {
+        driver.set_path(PathBuf::from(path));
+    }
 
-    [define factorial ((n int))
-        {if [= [:n] 0]
-            {return 1}
-            {* [:n] [factorial [- [:n] 1]]}
+    if let Some(dirs) = env::var_os("MIFUL_PATH") {
+        for dir in env::split_paths(&dirs) {
+            driver.add_search_path(dir);
         }
-    ]
-
-    [println
-        [obj-append
-            [string (Hello: [:space])]
-
-            ([input
-                [string
-                    (Enter [:space] your [:space] name: [:space])
-                ]
-            ])
-        ]
-    ]
-
-    [println
-        [string
-            [length
-                [obj-unwrap
-                    [input
-                        [string ([:l_bracket] input [:r_bracket] [:space])]
-                    ]
-                ]
-            ]
-        ]
-    ]
-
-    [println [string [factorial 12]]]
-    ";
-
-    let mut driver = driver::Driver::new(test);
-
-    let may_err = driver.process();
-
-    if let Err(e) = may_err {
-        e.throw_err();
     }
 
-    let result: Result<Vec<_>, _> = driver.collect();
+    parse(&mut driver);
+    evaluate(driver, false);
+}
 
-    match result {
-        Ok(ret) => {
-            for v in ret {
-                println!("[{}]: ` {} `", v.node, v);
-            }
-        },
+fn eval(expression: &str) {
+    let source = if expression == "-" { read_stdin() } else { expression.to_owned() };
+    let mut driver = Driver::new(&source);
 
-        Err(e) => {
-            e.throw_err();// [TODO] Maybe don't panic?
-        },
+    parse(&mut driver);
+    evaluate(driver, true);
+}
+
+fn check(path: &str) {
+    let source = read_file(path);
+    let mut driver = Driver::new(&source);
+
+    let errors = driver.check();
+
+    if !errors.is_empty() {
+        for e in &errors {
+            report(e);
+        }
+
+        process::exit(EXIT_PARSE);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.is_empty() {
+        Repl::new(Driver::new(""), Renderer::for_stderr()).run();
+
+        return;
+    }
+
+    if args.len() < 2 {
+        usage();
+    }
+
+    match args[0].as_ref() {
+        "run" => { run(&args[1], args[2 ..].to_vec()); },
+
+        "eval" if args.len() == 2 => { eval(&args[1]); },
+        "check" if args.len() == 2 => { check(&args[1]); },
+
+        _ => { usage(); },
     }
 }
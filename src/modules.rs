@@ -0,0 +1,149 @@
+use parsing::ast::{ NodeWrapper, MifulType };
+
+use std::collections::HashMap;
+use std::path::{ Path, PathBuf };
+use std::rc::Rc;
+
+
+/*
+
+# Modules
+
+    A script can use the definitions of another file:
+
+    ```
+    [import "items"]
+    [items/potion [: hero]]
+    ```
+
+    `import` runs `items.mf` and defines its functions qualified by the name of
+the file, `require` defines them as they are. An optional second argument of
+`import` gives another namespace:
+
+    ```
+    [import "content/items" it]
+    [it/potion [: hero]]
+    ```
+
+    A file is run only once, importing it again reuses its definitions. A file
+importing itself, directly or through other files, is a runtime error.
+
+    A file exports all its functions unless it says which with `export`:
+
+    ```
+    [export potion elixir]
+    ```
+
+    Structures are always shared, under their own names.
+
+## Resolution
+
+    A path without an extension gets `.mf`. It's looked up relative to the importing
+file (the working directory for a source without a file), then in every
+directory of the search path, in order.
+
+*/
+
+
+pub type Functions = HashMap<(String, Vec<MifulType>), (Vec<String>, NodeWrapper)>;
+
+// [NOTE] What a file leaves behind after running. Bodies of the functions see
+// the other functions of the file, exported or not.
+//
+pub struct Module {
+    pub functions: Functions,
+    pub structs: HashMap<String, Vec<(String, MifulType)>>,
+}
+
+// [NOTE] Shared by every driver created while running, including the drivers of
+// imported files.
+//
+pub struct Modules {
+    loaded: HashMap<PathBuf, Rc<Module>>,
+
+    // [NOTE] Files being run right now, the importing one first.
+    //
+    loading: Vec<PathBuf>,
+
+    search_path: Vec<PathBuf>,
+}
+
+impl Modules {
+    pub fn new() -> Modules {
+        Modules {
+            loaded: HashMap::new(),
+            loading: vec![],
+
+            search_path: vec![],
+        }
+    }
+
+    pub fn add_search_path(&mut self, dir: PathBuf) {
+        self.search_path.push(dir);
+    }
+
+    // [NOTE] Finds the file of `name` imported from `importer`.
+    //
+    pub fn resolve(&self, name: &str, importer: &Option<PathBuf>) -> Option<PathBuf> {
+        let mut file = PathBuf::from(name);
+
+        if file.extension().is_none() {
+            file.set_extension("mf");
+        }
+
+        let base = match importer {
+            Some(path) => { path.parent().map(Path::to_path_buf).unwrap_or_default() },
+            None => { PathBuf::new() },
+        };
+
+        let candidates = Some(base).into_iter().chain(self.search_path.iter().cloned());
+
+        for dir in candidates {
+            let path = dir.join(&file);
+
+            if path.is_file() {
+                return Some(path.canonicalize().unwrap_or(path));
+            }
+        }
+
+        None
+    }
+
+    pub fn get(&self, path: &Path) -> Option<Rc<Module>> {
+        self.loaded.get(path).cloned()
+    }
+
+    // [NOTE] Returns the chain of imports leading back to `path` if it's already
+    // being run, e.g. `a.mf -> b.mf -> a.mf`.
+    //
+    pub fn start(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(i) = self.loading.iter().position(|p| p == path) {
+            let chain: Vec<String> = self.loading[i ..].iter()
+                .chain(Some(&path.to_path_buf()))
+                .map(|p| p.display().to_string())
+                .collect();
+
+            return Err(chain.join(" -> "));
+        }
+
+        self.loading.push(path.to_path_buf());
+
+        Ok(())
+    }
+
+    // [NOTE] Ends running `path`, remembering its module if it ran without errors.
+    //
+    pub fn finish(&mut self, path: &Path, module: Option<Module>) {
+        self.loading.retain(|p| p != path);
+
+        if let Some(m) = module {
+            self.loaded.insert(path.to_path_buf(), Rc::new(m));
+        }
+    }
+}
+
+impl Default for Modules {
+    fn default() -> Modules {
+        Modules::new()
+    }
+}
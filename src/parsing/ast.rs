@@ -1,7 +1,11 @@
+use parsing::span::Span;
+
+use std::rc::Rc;
+use std::collections::HashMap;
 use std::fmt::{ self, Formatter, Display };
 
 
-fn format_node(node: &NodeKind, hooks: &Vec<NodeWrapper>, layer: usize, include_initial_indent: bool) -> String {
+fn format_node(node: &NodeKind, hooks: &[NodeWrapper], layer: usize, include_initial_indent: bool) -> String {
     let indent = "   ".repeat(layer).to_owned();
     let mut output;
 
@@ -14,12 +18,12 @@ fn format_node(node: &NodeKind, hooks: &Vec<NodeWrapper>, layer: usize, include_
 
     match node {
         NodeKind::Word(val) => {
-            output.push_str(&val);
+            output.push_str(val);
         },
 
 
         NodeKind::Symbol(val) => {
-            output.push_str(&val);
+            output.push_str(val);
         },
 
 
@@ -28,18 +32,20 @@ fn format_node(node: &NodeKind, hooks: &Vec<NodeWrapper>, layer: usize, include_
         },
 
 
+        NodeKind::Str(val) => {
+            output.push_str(&escape_str(val));
+        },
+
+
         NodeKind::Int(val) => {
             output.push_str(&val.to_string());
         },
 
         NodeKind::List(lst) => {
-            output.push_str("(");
+            output.push('(');
 
             for wrapper in lst {
-                let mut inner_hooks = wrapper.hooks.clone();
-                inner_hooks.splice(0..0, hooks.clone());
-
-                let current = format_node(&wrapper.node, &inner_hooks, layer + 1, false) + " ";
+                let current = format_node(&wrapper.node, &wrapper.hooks, layer + 1, false) + " ";
 
                 output.push_str(&current);
             }
@@ -48,18 +54,15 @@ fn format_node(node: &NodeKind, hooks: &Vec<NodeWrapper>, layer: usize, include_
                 output.pop();
             }
 
-            output.push_str(")");
+            output.push(')');
         },
 
         NodeKind::Quote{ target, with } => {
-            output.push_str("{");
+            output.push('{');
             output.push_str(&(target.to_string() + "\n"));
 
             for wrapper in with {
-                let mut inner_hooks = wrapper.hooks.clone();
-                inner_hooks.splice(0..0, hooks.clone());
-
-                let current = format_node(&wrapper.node, &inner_hooks, layer + 1, true) + "\n";
+                let current = format_node(&wrapper.node, &wrapper.hooks, layer + 1, true) + "\n";
 
                 output.push_str(&current);
             }
@@ -67,26 +70,26 @@ fn format_node(node: &NodeKind, hooks: &Vec<NodeWrapper>, layer: usize, include_
             output.push_str(&(indent + "}"));
         },
 
-        NodeKind::LambdaHook(v_idx) => {
-            let idx = v_idx.clone();
-            let node = &hooks[idx];
+        NodeKind::LambdaHook(_) => {
+            let node = &hooks[0];
 
             output.push_str("{? ");
 
-            output.push_str(&format_node(&node.node, &vec![], layer + 1, false));// [TODO] Nested hooks?
+            output.push_str(&format_node(&node.node, &node.hooks, layer + 1, false));
 
             output.push_str(" ?}");
         },
 
+        NodeKind::Error(message) => {
+            output.push_str(&format!("<error: {}>", message));
+        },
+
         NodeKind::Invoke{ target, with } => {
-            output.push_str("[");
+            output.push('[');
             output.push_str(&(target.to_string() + "\n"));
 
             for wrapper in with {
-                let mut inner_hooks = wrapper.hooks.clone();
-                inner_hooks.splice(0..0, hooks.clone());
-
-                let current = format_node(&wrapper.node, &inner_hooks, layer + 1, true) + "\n";
+                let current = format_node(&wrapper.node, &wrapper.hooks, layer + 1, true) + "\n";
 
                 output.push_str(&current);
             }
@@ -99,19 +102,48 @@ fn format_node(node: &NodeKind, hooks: &Vec<NodeWrapper>, layer: usize, include_
 }
 
 
+// [NOTE] Writes `val` back as a string literal.
+//
+pub fn escape_str(val: &str) -> String {
+    let mut output = String::from("\"");
+
+    for c in val.chars() {
+        match c {
+            '"' => { output.push_str("\\\""); },
+            '\\' => { output.push_str("\\\\"); },
+            '\n' => { output.push_str("\\n"); },
+            '\t' => { output.push_str("\\t"); },
+            '\r' => { output.push_str("\\r"); },
+            '\0' => { output.push_str("\\0"); },
+
+            _ => { output.push(c); },
+        }
+    }
+
+    output.push('"');
+
+    output
+}
+
+
 #[derive(Clone, Debug)]
 pub enum NodeKind {
     Word(String),
     Symbol(String),
+    Str(String),
 
     Float(f64),
     Int(i64),
 
     List(Vec<NodeWrapper>),
 
-    LambdaHook(usize),
+    LambdaHook(usize),// [NOTE] Number of the hook within its quote.
     Quote{ target: String, with: Vec<NodeWrapper> },
     Invoke{ target: String, with: Vec<NodeWrapper> },
+
+    // [NOTE] Stands in for a structure that failed to parse, see `Parser::new_recovering`.
+    //
+    Error(String),
 }
 
 impl Display for NodeKind {
@@ -119,6 +151,7 @@ impl Display for NodeKind {
         match &self {
             NodeKind::Word(_) => write!(f, "Word"),
             NodeKind::Symbol(_) => write!(f, "Symbol"),
+            NodeKind::Str(_) => write!(f, "Str"),
 
             NodeKind::Float(_) => write!(f, "Float"),
             NodeKind::Int(_) => write!(f, "Int"),
@@ -128,6 +161,8 @@ impl Display for NodeKind {
             NodeKind::LambdaHook(_) => write!(f, "LambdaHook"),
             NodeKind::Quote{ target:_, with:_ } => write!(f, "Quote"),
             NodeKind::Invoke{ target:_, with:_ } => write!(f, "Invoke"),
+
+            NodeKind::Error(_) => write!(f, "Error"),
         }
     }
 }
@@ -136,10 +171,16 @@ impl Display for NodeKind {
 #[derive(Clone, Debug)]
 pub struct NodeWrapper {
     pub node: NodeKind,
+
+    // [NOTE] Only used by hooks, holding the unquoted expression.
+    //
     pub hooks: Vec<NodeWrapper>,
 
-    pub position: (usize, usize),
-    pub index: usize,
+    pub span: Span,
+
+    // [NOTE] Set only on evaluated quotes, never by the parser.
+    //
+    pub closure: Option<Rc<Closure>>,
 }
 
 impl Display for NodeWrapper {
@@ -154,55 +195,71 @@ impl NodeWrapper {
             node: NodeKind::List(vec![]),
             hooks: vec![],
 
-            position: (0, 0),
-            index: 0,
+            span: Span::default(),
+
+            closure: None,
         }
     }
 
 
     // [AREA] Structure Nodes
     //
-    pub fn new_list(elements: Vec<NodeWrapper>, hooks: Vec<NodeWrapper>, index: usize, position: (usize, usize)) -> NodeWrapper {
+    pub fn new_list(elements: Vec<NodeWrapper>, hooks: Vec<NodeWrapper>, span: Span) -> NodeWrapper {
         NodeWrapper {
             node: NodeKind::List(elements),
             hooks,
 
-            position,
-            index,
+            span,
+
+            closure: None,
         }
     }
 
-    pub fn new_invoke(target: String, with: Vec<NodeWrapper>, hooks: Vec<NodeWrapper>, index: usize, position: (usize, usize))
+    pub fn new_invoke(target: String, with: Vec<NodeWrapper>, hooks: Vec<NodeWrapper>, span: Span)
         -> NodeWrapper {
 
         NodeWrapper {
             node: NodeKind::Invoke { target: target.to_owned(), with },
             hooks,
 
-            position,
-            index,
+            span,
+
+            closure: None,
         }
     }
 
-    pub fn new_quote(target: String, with: Vec<NodeWrapper>, hooks: Vec<NodeWrapper>, index: usize, position: (usize, usize))
+    pub fn new_quote(target: String, with: Vec<NodeWrapper>, hooks: Vec<NodeWrapper>, span: Span)
         -> NodeWrapper {
 
         NodeWrapper {
             node: NodeKind::Quote { target: target.to_owned(), with },
             hooks,
 
-            position,
-            index,
+            span,
+
+            closure: None,
         }
     }
 
-    pub fn new_hook(v_idx: usize, hooks: Vec<NodeWrapper>, index: usize, position: (usize, usize)) -> NodeWrapper {
+    pub fn new_hook(v_idx: usize, hooks: Vec<NodeWrapper>, span: Span) -> NodeWrapper {
         NodeWrapper {
             node: NodeKind::LambdaHook(v_idx),
-            hooks,// [NOTE] Not nested hooks, just the contained value.
+            hooks,// [NOTE] Just the contained expression.
+
+            span,
 
-            position,
-            index,
+            closure: None,
+        }
+    }
+
+    pub fn new_error(message: &str, span: Span) -> NodeWrapper {
+        NodeWrapper {
+            node: NodeKind::Error(message.to_owned()),
+            hooks: vec![],
+
+            span,
+
+            closure: None,
         }
     }
     //
@@ -211,47 +268,95 @@ impl NodeWrapper {
 
     // [AREA] Value Nodes
     //
-    pub fn new_word(value: String, index: usize, position: (usize, usize)) -> NodeWrapper {
+    pub fn new_word(value: String, span: Span) -> NodeWrapper {
         NodeWrapper {
             node: NodeKind::Word(value.to_owned()),
             hooks: vec![],
 
-            position,
-            index,
+            span,
+
+            closure: None,
         }
     }
 
-    pub fn new_symbol(value: String, index: usize, position: (usize, usize)) -> NodeWrapper {
+    pub fn new_symbol(value: String, span: Span) -> NodeWrapper {
         NodeWrapper {
             node: NodeKind::Symbol(value.to_owned()),
             hooks: vec![],
 
-            position,
-            index,
+            span,
+
+            closure: None,
+        }
+    }
+
+    pub fn new_str(value: String, span: Span) -> NodeWrapper {
+        NodeWrapper {
+            node: NodeKind::Str(value),
+            hooks: vec![],
+
+            span,
+
+            closure: None,
         }
     }
 
-    pub fn new_float(value: f64, index: usize, position: (usize, usize)) -> NodeWrapper {
+    pub fn new_float(value: f64, span: Span) -> NodeWrapper {
         NodeWrapper {
             node: NodeKind::Float(value),
             hooks: vec![],
 
-            position,
-            index,
+            span,
+
+            closure: None,
         }
     }
 
-    pub fn new_int(value: i64, index: usize, position: (usize, usize)) -> NodeWrapper {
+    pub fn new_int(value: i64, span: Span) -> NodeWrapper {
         NodeWrapper {
             node: NodeKind::Int(value),
             hooks: vec![],
 
-            position,
-            index,
+            span,
+
+            closure: None,
         }
     }
     //
     // [END] Value Node
+
+    pub fn with_closure(self, closure: Closure) -> NodeWrapper {
+        NodeWrapper {
+            closure: Some(Rc::new(closure)),
+
+            ..self
+        }
+    }
+}
+
+
+// [NOTE] The environment a quote was created in. Quotes created by `lambda`
+// also carry their parameters, other quotes have `None`.
+//
+#[derive(Clone, Debug)]
+pub struct Closure {
+    pub params: Option<Vec<(String, MifulType)>>,
+
+    pub scope: HashMap<String, NodeWrapper>,
+    pub functions: HashMap<(String, Vec<MifulType>), (Vec<String>, NodeWrapper)>,
+}
+
+impl Closure {
+    pub fn new(params: Option<Vec<(String, MifulType)>>, scope: HashMap<String, NodeWrapper>,
+        functions: HashMap<(String, Vec<MifulType>), (Vec<String>, NodeWrapper)>) -> Closure {
+
+        Closure {
+            params,
+
+            scope,
+            functions,
+        }
+    }
 }
 
 
@@ -0,0 +1,262 @@
+use std::cmp;
+use std::env;
+use std::io::{ self, IsTerminal };
+
+use parsing::span::Span;
+use parsing::utils::{ Error, Frame };
+
+
+// [NOTE] Spans of longer than this many lines get their middle lines left out.
+//
+const MAX_SNIPPET_LINES: usize = 6;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+
+
+// [NOTE] Secondary location attached to an error, like the definition
+// of a function that couldn't be called.
+//
+#[derive(Clone, Debug)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(span: Span, message: &str) -> Label {
+        Label {
+            span,
+            message: message.to_owned(),
+        }
+    }
+}
+
+
+// [NOTE] Renders errors as the lines of the source they point to, with the erroneous
+// span underlined by `^`, secondary labels by `-`, the context the error was
+// found in listed as notes, and the calls it went through as a backtrace:
+//
+//    Runtime error [R004]: Division by zero!
+//      --> 1:33
+//      |
+//    1 | [define half ((n int)) {/ [: n] 0}]
+//      |                                 ^
+//      |
+//      = note: while evaluating argument 1 of print at 2:8
+//      = backtrace:
+//        0: half at 2:8
+//             [print [half 4]]
+//
+pub struct Renderer {
+    colour: bool,
+}
+
+impl Renderer {
+    pub fn new(colour: bool) -> Renderer {
+        Renderer {
+            colour,
+        }
+    }
+
+    // [NOTE] Plain text, for logs and files.
+    //
+    pub fn plain() -> Renderer {
+        Renderer::new(false)
+    }
+
+    // [NOTE] Colours the output only if the standard output is a terminal
+    // and `NO_COLOR` isn't set.
+    //
+    pub fn for_stdout() -> Renderer {
+        if io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none() {
+            Renderer::new(true)
+
+        } else {
+            Renderer::plain()
+        }
+    }
+
+    // [NOTE] The same for the standard error.
+    //
+    pub fn for_stderr() -> Renderer {
+        if io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none() {
+            Renderer::new(true)
+
+        } else {
+            Renderer::plain()
+        }
+    }
+
+    pub fn render<E: Error + ?Sized>(&self, e: &E) -> String {
+        let source = e.get_source();
+        let span = e.get_span();
+        let labels = e.get_labels();
+
+        let description = e.get_description();
+        let mut description_lines = description.lines();
+
+        let headline = description_lines.next().unwrap_or("");
+
+        let last_line = labels.iter()
+            .map(|l| l.span.end_pos.0)
+            .fold(span.end_pos.0, cmp::max);
+
+        let pad = " ".repeat(last_line.to_string().len());
+
+        let mut output = format!("{}: {}\n",
+            self.paint(RED, &format!("{} error [{}]", e.get_kind(), e.get_code())),
+            self.paint(BOLD, headline));
+
+        // [NOTE] The default span stands for an unknown location.
+        //
+        if span.start_pos.0 > 0 {
+            output.push_str(&format!("{} {} {}:{}\n", pad, self.paint(BLUE, "-->"), span.start_pos.0, span.start_pos.1));
+
+            if !source.is_empty() {
+                output.push_str(&self.snippet(source, span, "^", RED, "", &pad));
+
+                for label in labels {
+                    output.push_str(&self.snippet(source, label.span, "-", BLUE, &label.message, &pad));
+                }
+
+                output.push_str(&format!("{} {}\n", pad, self.paint(BLUE, "|")));
+            }
+        }
+
+        for line in description_lines {
+            output.push_str(&format!("{} {} {}\n", pad, self.paint(BLUE, "="), line.trim()));
+        }
+
+        // [NOTE] Innermost context first.
+        //
+        for frame in e.get_context().iter().rev().filter(|frame| !frame.is_call()) {
+            output.push_str(&format!("{} {} {}\n", pad, self.paint(BLUE, "= note:"), frame));
+        }
+
+        let backtrace = e.get_backtrace();
+
+        if !backtrace.is_empty() {
+            output.push_str(&format!("{} {}\n", pad, self.paint(BLUE, "= backtrace:")));
+
+            for (depth, frame) in backtrace.into_iter().enumerate() {
+                output.push_str(&self.backtrace_entry(source, span.file, frame, depth, &pad));
+            }
+        }
+
+        output
+    }
+
+    // [NOTE] A call of the backtrace, followed by the line of its call site if it's
+    // in `source` (of `file`).
+    //
+    fn backtrace_entry(&self, source: &[String], file: usize, frame: &Frame, depth: usize, pad: &str) -> String {
+        let name = match &frame.name {
+            Some(name) => name.as_ref(),
+            None => "?",
+        };
+
+        let mut output = if frame.span.start_pos.0 > 0 {
+            format!("{}   {}: {} at {}:{}\n", pad, depth, self.paint(BOLD, name), frame.span.start_pos.0, frame.span.start_pos.1)
+
+        } else {
+            format!("{}   {}: {}\n", pad, depth, self.paint(BOLD, name))
+        };
+
+        if frame.span.start_pos.0 > 0 && frame.span.file == file && frame.span.start < source.len() {
+            let is_newline = |g: &String| g == "\n" || g == "\r\n";
+
+            let line_start = source[.. frame.span.start].iter().rposition(&is_newline).map_or(0, |i| i + 1);
+            let line_end = source[line_start ..].iter().position(&is_newline).map_or(source.len(), |i| line_start + i);
+
+            let text: String = source[line_start .. line_end].concat();
+
+            output.push_str(&format!("{}        {}\n", pad, text.trim()));
+        }
+
+        output
+    }
+
+    fn paint(&self, colour: &str, text: &str) -> String {
+        if self.colour {
+            format!("{}{}{}", colour, text, RESET)
+
+        } else {
+            text.to_owned()
+        }
+    }
+
+    // [NOTE] Every source line `span` touches, each followed by the underline
+    // of its part of the span. `label` goes after the last underline.
+    //
+    fn snippet(&self, source: &[String], span: Span, mark: &str, colour: &str, label: &str, pad: &str) -> String {
+        let is_newline = |g: &String| g == "\n" || g == "\r\n";
+
+        let start = cmp::min(span.start, source.len());
+        let end = cmp::max(cmp::min(span.end, source.len()), start);
+
+        let mut output = format!("{} {}\n", pad, self.paint(BLUE, "|"));
+
+        // [NOTE] (first grapheme, end) of every line of the span.
+        //
+        let mut lines = vec![];
+        let mut line_start = source[.. start].iter().rposition(&is_newline).map_or(0, |i| i + 1);
+
+        loop {
+            let line_end = source[line_start ..].iter().position(&is_newline).map_or(source.len(), |i| line_start + i);
+
+            lines.push((line_start, line_end));
+
+            if line_end >= end || line_end >= source.len() {
+                break;
+            }
+
+            line_start = line_end + 1;
+        }
+
+        let line_count = lines.len();
+
+        for (i, (line_start, line_end)) in lines.into_iter().enumerate() {
+            if line_count > MAX_SNIPPET_LINES && i >= MAX_SNIPPET_LINES / 2 && i < line_count - MAX_SNIPPET_LINES / 2 {
+                if i == MAX_SNIPPET_LINES / 2 {
+                    output.push_str(&format!("{} {}\n", pad, self.paint(BLUE, "...")));
+                }
+
+                continue;
+            }
+
+            let text: String = source[line_start .. line_end]
+                .iter()
+                .map(|g| match g.as_ref() {
+                    "\t" | "\r" => { " " },
+
+                    _ => { g.as_ref() },
+                })
+                .collect();
+
+            // [NOTE] Continuation lines are underlined from their first non-blank grapheme.
+            //
+            let first_visible = source[line_start .. line_end].iter().position(|g| !g.trim().is_empty()).map_or(line_end, |p| line_start + p);
+
+            let mark_start = if i == 0 { start } else { first_visible };
+            let mark_end = cmp::max(cmp::min(end, line_end), mark_start + (i == 0) as usize);
+
+            let number = format!("{:>width$}", span.start_pos.0 + i, width = pad.len());
+
+            output.push_str(&format!("{} {} {}\n", self.paint(BLUE, &number), self.paint(BLUE, "|"), text));
+
+            let mut underline = format!("{}{}", " ".repeat(mark_start - line_start), mark.repeat(mark_end.saturating_sub(mark_start)));
+
+            if i == line_count - 1 && !label.is_empty() {
+                underline.push(' ');
+                underline.push_str(label);
+            }
+
+            output.push_str(&format!("{} {} {}\n", pad, self.paint(BLUE, "|"), self.paint(colour, &underline)));
+        }
+
+        output
+    }
+}
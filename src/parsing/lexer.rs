@@ -1,57 +1,64 @@
+use std::cmp;
 use std::collections::HashSet;
-use parsing::token::Token;
+use parsing::span::{ Span, SourceMap };
+use parsing::token::{ Token, TokenType };
+use parsing::utils::{ MifulError, codes };
 
 
 #[derive(Debug)]
 pub struct Lexer<'outer, 'inner> {
-    tokens: Vec<Token>,
-
-    position: (usize, usize),
+    map: SourceMap,
     index: usize,
     span: usize,
 
     string: Vec<&'outer str>,
-    work_string: &'outer str,
 
     special_chars: HashSet<&'inner str>,
 
     symbols: HashSet<&'inner str>,
-    number: HashSet<&'inner str>,
 
     ws: HashSet<&'inner str>,
 
     keep_ws: bool,
+    keep_comments: bool,
+
+    open_hooks: usize,
+
+    // [NOTE] Errors found so far. The lexer goes on after an error,
+    // lexing the offending text as a word or skipping it.
+    //
+    errors: Vec<MifulError>,
 }
 
 impl<'outer, 'inner> Lexer<'outer, 'inner> {
     pub fn new(input: Vec<&'outer str>, symbols: HashSet<&'inner str>) -> Lexer<'outer, 'inner> {
-        let mut fused = vec!["[", "]", "{", "}", "{?", "?}", "(", ")", " ", "\n", "\t", "\r"];
+        let mut fused = vec!["[", "]", "{", "}", "{?", "?}", "(", ")", "\"", ";", " ", "\n", "\t", "\r"];
         fused.extend(symbols.iter().cloned());
 
         let special_chars: HashSet<&'inner str> = fused.iter().map(|x| &**x).collect();
 
         Lexer {
-            tokens: vec![],
-
-            position: (1, 1),
+            map: SourceMap::new(&input),
             index: 0,
             span: 1,
 
             special_chars,
 
-            work_string: "",
             string: input,
 
             symbols,
-            number: set!["1", "2", "3", "4", "5", "6", "7", "8", "9", "0"],
 
             ws: set![" ", "\n", "\t", "\r"],
             keep_ws: false,
+            keep_comments: false,
+
+            open_hooks: 0,
+            errors: vec![],
         }
     }
 
     pub fn new_ws_preserving(input: Vec<&'outer str>, symbols: HashSet<&'inner str>) -> Lexer<'outer, 'inner> {
-        let mut fused = vec!["[", "]", "{", "}", "{?", "?}", "(", ")", " ", "\n", "\t", "\r"];
+        let mut fused = vec!["[", "]", "{", "}", "{?", "?}", "(", ")", "\"", ";", " ", "\n", "\t", "\r"];
         fused.extend(symbols.iter().cloned());
 
         let special_chars: HashSet<&'inner str> = fused.iter().map(|x| &**x).collect();
@@ -60,38 +67,48 @@ impl<'outer, 'inner> Lexer<'outer, 'inner> {
         all_symbols.extend([" ", "\n", "\t", "\r"].iter());
 
         Lexer {
-            tokens: vec![],
-
-            position: (1, 1),
+            map: SourceMap::new(&input),
             index: 0,
             span: 1,
 
             special_chars,
 
-            work_string: "",
             string: input,
 
             symbols: all_symbols,
-            number: set!["1", "2", "3", "4", "5", "6", "7", "8", "9", "0"],
 
             ws: set![" ", "\n", "\t", "\r"],
             keep_ws: true,
+            keep_comments: false,
+
+            open_hooks: 0,
+            errors: vec![],
         }
     }
 
+    // [NOTE] Like `new_ws_preserving`, but comments are returned as `Comment` tokens
+    // instead of being skipped, e.g. for a formatter.
+    //
+    pub fn new_comment_preserving(input: Vec<&'outer str>, symbols: HashSet<&'inner str>) -> Lexer<'outer, 'inner> {
+        let mut lexer = Lexer::new_ws_preserving(input, symbols);
+
+        lexer.keep_comments = true;
+
+        lexer
+    }
+
 
     // [AREA] Mutating
     //
-    fn advance(&mut self, new_span: usize) {
-        self.position.1 += self.span;
+    fn report(&mut self, code: &'static str, message: &str, span: Span) {
+        let source: Vec<String> = self.string.iter().map(|s| s.to_string()).collect();
 
-        self.index += self.span;
-        self.span = new_span;
+        self.errors.push(MifulError::parse_error(message, &source, span).with_code(code));
     }
 
-    fn next_line(&mut self) {
-        self.position.0 += 1;
-        self.position.1 = 1;
+    fn advance(&mut self, new_span: usize) {
+        self.index += self.span;
+        self.span = new_span;
     }
 
     fn step_forward(&mut self) {
@@ -107,6 +124,10 @@ impl<'outer, 'inner> Lexer<'outer, 'inner> {
 
     // [AREA] Copying Fields
     //
+    pub fn errors(&self) -> &Vec<MifulError> {
+        &self.errors
+    }
+
     fn get_workspan(&self) -> &[&'outer str] {
         &self.string[self.index .. self.index + self.span]
     }
@@ -124,8 +145,19 @@ impl<'outer, 'inner> Lexer<'outer, 'inner> {
         s.iter().all(|x| !self.is_special(x))
     }
 
-    fn is_int(&self, s: &Vec<&str>) -> bool {
-        s.iter().all(|x| self.number.contains(x))
+    // [NOTE] A word may end with `?`, like `defined?`, if nothing else follows it.
+    // `x?}` is still `x` closing an unquote.
+    //
+    fn is_predicate(&self, s: &Vec<&str>) -> bool {
+        let (last, word) = match s.split_last() {
+            Some(split) => { split },
+            None => { return false; },
+        };
+
+        let next = self.string.get(self.index + self.span).cloned();
+        let ends = next.map_or(true, |c| c != "}" && c != "?" && self.is_special(c));
+
+        *last == "?" && !word.is_empty() && self.is_word(&word.to_vec()) && Lexer::parse_number(&word.join("")).is_none() && ends
     }
 
     fn is_word_symbol(&self, s: &Vec<&str>) -> bool {
@@ -145,67 +177,312 @@ impl<'outer, 'inner> Lexer<'outer, 'inner> {
         }
     }
 
-    // [NOTE] Returns `true` iff the number has decimal point.
+    // [NOTE] Number literals are:
+    //    [-] digits [. [digits]] [(e | E) [+ | -] digits]
+    //    [-] (0x | 0X) hex-digits
+    //    [-] (0b | 0B) binary-digits
+    // ..where digits may be separated by single `_`. A literal with a decimal point
+    // or an exponent is a float.
+    //
+    // [NOTE] Returns `None` if `s` doesn't follow the grammar (so it's a word),
+    // and `Some(Err(..))` if it does, but the value doesn't fit.
     //
-    fn is_float(&self, s: &Vec<&str>) -> bool {
-        let mut saw_dot = false;
+    // [NOTE] Also used by `to-int` and `to-float`, so strings read like the source.
+    //
+    pub fn parse_number(s: &str) -> Option<Result<TokenType, String>> {
+        let (sign, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => { ("-", rest) },
+            None => { ("", s) },
+        };
+
+        let radix_digits = |digits: &str, radix: u32| {
+            digits.split('_').all(|group| !group.is_empty() && group.chars().all(|c| c.is_digit(radix)))
+        };
+
+        // [NOTE] Prefixes are matched on `unsigned` itself, as changing the case
+        // of other letters may change their length.
+        //
+        let prefixed = |p: char| {
+            let mut chars = unsigned.chars();
+
+            chars.next() == Some('0') && chars.next().map(|c| c.to_ascii_lowercase()) == Some(p)
+        };
+
+        if prefixed('x') || prefixed('b') {
+            let radix = if prefixed('x') { 16 } else { 2 };
+            let digits = &unsigned[2..];
+
+            if !radix_digits(digits, radix) {
+                return None;
+            }
+
+            let cleaned = format!("{}{}", sign, digits.replace("_", ""));
 
-        for x in s.iter() {
-            if !self.number.contains(x) && (saw_dot || x.to_owned() != ".") {
-                return false;
+            return Some(
+                i64::from_str_radix(&cleaned, radix)
+                    .map(TokenType::Int)
+                    .map_err(|_| format!("Integer literal ` {} ` is out of range!", s))
+            );
+        }
 
-            } else if x.to_owned() == "." {
-                saw_dot = true;
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(i) => (&unsigned[.. i], Some(&unsigned[i + 1 ..])),
+            None => (unsigned, None),
+        };
+
+        let (whole, fraction) = match mantissa.find('.') {
+            Some(i) => (&mantissa[.. i], Some(&mantissa[i + 1 ..])),
+            None => (mantissa, None),
+        };
+
+        // [NOTE] Either side of the decimal point may be empty, not both.
+        //
+        let part_ok = |part: &str| part.is_empty() || radix_digits(part, 10);
+
+        if whole.len() + fraction.map_or(0, str::len) == 0 || !part_ok(whole) || !fraction.map_or(true, part_ok) {
+            return None;
+        }
+
+        if let Some(e) = exponent {
+            let e_digits = e.strip_prefix(|c| c == '-' || c == '+').unwrap_or(e);
+
+            if e_digits.is_empty() || !e_digits.chars().all(|c| c.is_ascii_digit()) {
+                return None;
             }
         }
 
-        saw_dot
+        let cleaned = format!("{}{}", sign, unsigned.replace("_", ""));
+
+        if fraction.is_none() && exponent.is_none() {
+            Some(
+                cleaned.parse::<i64>()
+                    .map(TokenType::Int)
+                    .map_err(|_| format!("Integer literal ` {} ` is out of range!", s))
+            )
+
+        } else {
+            match cleaned.parse::<f64>() {
+                Ok(f) if f.is_finite() => { Some(Ok(TokenType::Float(f))) },
+
+                _ => { Some(Err(format!("Float literal ` {} ` is out of range!", s))) },
+            }
+        }
     }
 
     fn is_literal(&self, s: &Vec<&str>) -> bool {
         let joint = s.join("");
         let joint_ref: &str = joint.as_ref();
 
-        self.is_word(s) || self.is_int(s) || self.is_float(s) || self.symbols.contains(joint_ref)
+        self.is_word(s) || self.symbols.contains(joint_ref)
     }
 
     fn is_special(&self, s: &str) -> bool {
-        match s {
-            _ if self.special_chars.contains(s) => { true },
+        self.special_chars.contains(s)
+    }
+    //
+    // [END] Checking Tokens
+
+    // [NOTE] Line comments run from `;` to the end of the line, block comments
+    // from `#|` to the matching `|#` and may be nested.
+    //
+    // [NOTE] `#|` always opens a comment, although `#` and `|` are symbols. The
+    // symbols need a space between them, like `# |`.
+    //
+    fn at_comment(&self) -> bool {
+        match self.string.get(self.index).cloned() {
+            Some(";") => { true },
+            Some("#") => { self.string.get(self.index + 1) == Some(&"|") },
+
             _ => { false },
         }
     }
+
+    // [NOTE] Reads the comment starting at the current position, and moves past its end.
+    // The line break after a line comment is left in place.
     //
-    // [END] Checking Tokens
+    fn read_comment(&mut self) -> Token {
+        let index = self.index;
+
+        let mut i = self.index;
+
+        let text = if self.string[i] == ";" {
+            while i < self.string.len() && self.string[i] != "\n" && self.string[i] != "\r\n" {
+                i += 1;
+            }
+
+            self.string[index + 1 .. i].join("")
+
+        } else {
+            let mut depth = 0;
 
-    fn process_literal(&self, workspan: Vec<&str>, old_span: usize) -> Token {
+            loop {
+                if i >= self.string.len() {
+                    let span = self.map.span(index, index + 2);
+
+                    self.report(codes::UNTERMINATED_COMMENT, "Unterminated block comment!", span);
+
+                    break;
+                }
+
+                let pair = (self.string[i], self.string.get(i + 1).cloned());
+
+                if pair == ("#", Some("|")) {
+                    depth += 1;
+
+                    i += 2;
+
+                } else if pair == ("|", Some("#")) {
+                    depth -= 1;
+
+                    i += 2;
+
+                    if depth == 0 {
+                        break;
+                    }
+
+                } else {
+                    i += 1;
+                }
+            }
+
+            // [NOTE] An unterminated comment runs to the end of input
+            //
+            let end = if depth == 0 { i - 2 } else { i };
+
+            self.string[index + 2 .. end].join("")
+        };
+
+        self.index = i;
+        self.span = 1;
+
+        Token::new_comment(&text, self.map.span(index, i))
+    }
+
+    // [NOTE] Reads a string literal starting at the current `"`, and moves past its end.
+    //
+    fn read_string(&mut self) -> Token {
+        let index = self.index;
+
+        let mut value = String::new();
+
+        let mut i = self.index + 1;
+
+        loop {
+            if i >= self.string.len() {
+                let span = self.map.span(index, index + 1);
+
+                self.report(codes::UNTERMINATED_STRING, "Unterminated string!", span);
+
+                break;
+            }
+
+            match self.string[i] {
+                "\"" => {
+                    break;
+                },
+
+                "\\" => {
+                    let escape_index = i;
+
+                    i += 1;
+
+                    match self.string.get(i).cloned() {
+                        Some("n") => { value.push('\n'); },
+                        Some("t") => { value.push('\t'); },
+                        Some("r") => { value.push('\r'); },
+                        Some("0") => { value.push('\0'); },
+                        Some("\\") => { value.push('\\'); },
+                        Some("\"") => { value.push('"'); },
+
+                        Some("u") if self.string.get(i + 1) == Some(&"{") => {
+                            let mut hex = String::new();
+
+                            i += 2;
+
+                            while i < self.string.len() && self.string[i] != "}" {
+                                hex.push_str(self.string[i]);
+
+                                i += 1;
+                            }
+
+                            let c = u32::from_str_radix(&hex, 16).ok().and_then(::std::char::from_u32);
+
+                            match c {
+                                Some(c) if i < self.string.len() => { value.push(c); },
+                                _ => {
+                                    let span = self.map.span(escape_index, cmp::min(i + 1, self.string.len()));
+
+                                    self.report(codes::INVALID_ESCAPE, "Invalid unicode escape!", span);
+                                },
+                            }
+                        },
+
+                        _ => {
+                            let span = self.map.span(escape_index, cmp::min(i + 1, self.string.len()));
+
+                            self.report(codes::INVALID_ESCAPE, "Invalid escape sequence!", span);
+                        },
+                    }
+                },
+
+                g => {
+                    value.push_str(g);
+                },
+            }
+
+            i += 1;
+        }
+
+        self.index = cmp::min(i + 1, self.string.len());
+        self.span = 1;
+
+        Token::new_str(&value, self.map.span(index, self.index))
+    }
+
+    // [NOTE] `index` is where the literal starts, the lexer has already moved past it.
+    //
+    fn process_literal(&mut self, workspan: Vec<&str>, index: usize) -> Token {
         let old_joint = workspan.join("");
         let old_s = old_joint.as_ref();
 
-        let pos = self.position;
-        let index = self.index;
+        let span = self.map.span(index, index + workspan.len());
 
         if self.symbols.contains(old_s) {
-            Token::new_symbol(old_s, pos, index, old_span - 1)
+            Token::new_symbol(old_s, span)
+
+        } else if let Some(number) = Lexer::parse_number(old_s) {
+            match number {
+                Ok(TokenType::Int(i)) => { Token::new_int(i, span) },
+                Ok(TokenType::Float(f)) => { Token::new_float(f, span) },
 
-        } else if self.is_int(&workspan) {
-            Token::new_int(old_s.parse::<i64>().unwrap(), pos, index, old_span)
+                Ok(_) => { unreachable!(); },// [UNREACHABLE]
 
-        } else if self.is_float(&workspan) {
-            Token::new_float(old_s.parse::<f64>().unwrap(), pos, index, old_span)
+                Err(message) => {
+                    self.report(codes::LITERAL_OUT_OF_RANGE, &message, span);
+
+                    Token::new_word(old_s, span)
+                },
+            }
 
         } else if self.is_word_symbol(&workspan) {
             let mut window = old_s[1..].to_owned();
 
             window.pop();
 
-            Token::new_symbol(&window, pos, index, old_span)
+            Token::new_symbol(&window, span)
+
+        } else if workspan.first() == Some(&"`") {
+            self.report(codes::UNTERMINATED_SYMBOL, "Unterminated symbol!", span);
+
+            Token::new_word(old_s, span)
 
         } else if self.is_word(&workspan) {
-            Token::new_word(old_s, pos, index, old_span)
+            Token::new_word(old_s, span)
 
         } else {
-            panic!("Invalid literal!");
+            self.report(codes::INVALID_LITERAL, &format!("Invalid literal ` {} `!", old_s), span);
+
+            Token::new_word(old_s, span)
         }
     }
 }
@@ -222,69 +499,92 @@ impl<'outer, 'inner> Iterator for Lexer<'outer, 'inner> {
                 self.span = self.string.len() - self.index;
             }
 
-            let mut workspan = self.get_workspan().to_vec();
+            if self.span == 1 && self.at_comment() {
+                let comment = self.read_comment();
 
-            if !self.keep_ws && self.is_space(&workspan) {
-                let newlines = workspan.iter().cloned().filter(|x| x.to_owned() == "\n").count();
+                if self.keep_comments {
+                    return Some(comment);
 
-                for _ in 0..newlines {
-                    self.next_line();
+                } else {
+                    continue;
                 }
+            }
+
+            let mut workspan = self.get_workspan().to_vec();
 
+            if !self.keep_ws && self.is_space(&workspan) {
                 self.advance(0);
 
             } else {
                 let joint = workspan.join("");
 
-                let pos = self.position;
                 let index = self.index;
-                let span = self.span;
+                let span = self.map.span(index, index + self.span);
 
                 match joint.as_ref() {
+                    "\"" => {
+                        return Some(self.read_string());
+                    },
+
                     "[" if !self.keep_ws => {
                         self.advance(1);
 
-                        return Some(Token::new_control("[", pos, index, span));
+                        return Some(Token::new_control("[", span));
                     },
 
                     "]" if !self.keep_ws => {
                         self.advance(1);
 
-                        return Some(Token::new_control("]", pos, index, span));
+                        return Some(Token::new_control("]", span));
                     },
 
 
-                    "{" if !self.keep_ws => { /* Wait for potential `{?` */ },
+                    "{" if !self.keep_ws && self.index + 1 < self.string.len() => { /* Wait for potential `{?` */ },
+                    "{" if !self.keep_ws => {
+                        self.advance(1);
+
+                        return Some(Token::new_control("{", span));
+                    },
+
                     "}" if !self.keep_ws => {
                         self.advance(1);
 
-                        return Some(Token::new_control("}", pos, index, span));
+                        return Some(Token::new_control("}", span));
                     },
 
 
                     "{?" if !self.keep_ws => {
                         self.advance(1);
+                        self.open_hooks += 1;
 
-                        return Some(Token::new_control("{?", pos, index, span));
+                        return Some(Token::new_control("{?", span));
                     },
 
                     "?}" if !self.keep_ws => {
                         self.advance(1);
 
-                        return Some(Token::new_control("?}", pos, index, span));
+                        if self.open_hooks == 0 {
+                            self.report(codes::STRAY_UNQUOTE_END, "Stray ` ?} `, no unquote to close!", span);
+
+                            continue;
+                        }
+
+                        self.open_hooks -= 1;
+
+                        return Some(Token::new_control("?}", span));
                     },
 
 
                     "(" if !self.keep_ws => {
                         self.advance(1);
 
-                        return Some(Token::new_control("(", pos, index, span));
+                        return Some(Token::new_control("(", span));
                     },
 
                     ")" if !self.keep_ws => {
                         self.advance(1);
 
-                        return Some(Token::new_control(")", pos, index, span));
+                        return Some(Token::new_control(")", span));
                     },
 
 
@@ -298,20 +598,20 @@ impl<'outer, 'inner> Iterator for Lexer<'outer, 'inner> {
 
                                 less_span.pop();
 
-                                if less_span.len() > 0 && self.is_word(&less_span) {
+                                if !less_span.is_empty() && self.is_word(&less_span) {
                                     self.step_back();
                                     self.advance(1);
 
                                     let joint = less_span.join("");
 
-                                    return Some(Token::new_symbol(&joint, pos, index, span - 1));
+                                    return Some(Token::new_symbol(&joint, self.map.span(index, index + less_span.len())));
 
                                 } else {
                                     self.advance(1);
 
                                     let joint = workspan.join("");
 
-                                    return Some(Token::new_symbol(&joint, pos, index, span));
+                                    return Some(Token::new_symbol(&joint, span));
                                 }
                             }
 
@@ -320,13 +620,18 @@ impl<'outer, 'inner> Iterator for Lexer<'outer, 'inner> {
                                 self.step_back();
                                 self.advance(1);
 
-                                return Some(Token::new_control("{", pos, index, span - 1));
+                                return Some(Token::new_control("{", self.map.span(index, index + 1)));
+
+                            } else if self.is_predicate(&workspan) {
+                                self.advance(1);
+
+                                return Some(Token::new_word(&joint, span));
 
                             } else if self.is_literal(&workspan) {
                                 if self.index + self.span >= self.string.len() {
                                     self.advance(1);
 
-                                    return Some(self.process_literal(workspan, span));
+                                    return Some(self.process_literal(workspan, index));
 
                                 } else {
                                     // [NOTE] Greedily eat literal.
@@ -338,7 +643,7 @@ impl<'outer, 'inner> Iterator for Lexer<'outer, 'inner> {
 
                                 self.advance(1);
 
-                                return Some(self.process_literal(workspan, span));
+                                return Some(self.process_literal(workspan, index));
                             }
                         }
                     }
@@ -349,3 +654,273 @@ impl<'outer, 'inner> Iterator for Lexer<'outer, 'inner> {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::Lexer;
+    use parsing::token::{ Token, TokenType };
+    use parsing::utils::{ MifulError, Error, codes, segment_text };
+
+    use std::collections::HashSet;
+
+    fn lex(src: &str) -> (Vec<Token>, Vec<MifulError>) {
+        let symbols: HashSet<&str> = set![":", "@", "&", "|", "#", "~", "?", "\\"];
+
+        let mut lexer = Lexer::new(segment_text(src), symbols);
+        let tokens = lexer.by_ref().collect();
+
+        (tokens, lexer.errors().clone())
+    }
+
+    fn single(src: &str) -> TokenType {
+        let (tokens, errors) = lex(src);
+
+        assert!(errors.is_empty(), "{}", src);
+        assert_eq!(tokens.len(), 1, "{}", src);
+
+        tokens[0].kind.clone()
+    }
+
+    #[test]
+    fn integer_literals() {
+        assert_eq!(single("42"), TokenType::Int(42));
+        assert_eq!(single("-42"), TokenType::Int(-42));
+        assert_eq!(single("1_000_000"), TokenType::Int(1_000_000));
+        assert_eq!(single("0xFF"), TokenType::Int(255));
+        assert_eq!(single("0Xff"), TokenType::Int(255));
+        assert_eq!(single("-0x10"), TokenType::Int(-16));
+        assert_eq!(single("0b1010"), TokenType::Int(10));
+        assert_eq!(single("9223372036854775807"), TokenType::Int(i64::MAX));
+        assert_eq!(single("-9223372036854775808"), TokenType::Int(i64::MIN));
+    }
+
+    #[test]
+    fn float_literals() {
+        assert_eq!(single("1.5"), TokenType::Float(1.5));
+        assert_eq!(single("-1.5"), TokenType::Float(-1.5));
+        assert_eq!(single("1."), TokenType::Float(1.0));
+        assert_eq!(single(".5"), TokenType::Float(0.5));
+        assert_eq!(single("1e3"), TokenType::Float(1000.0));
+        assert_eq!(single("1E-3"), TokenType::Float(0.001));
+        assert_eq!(single("2.5e+2"), TokenType::Float(250.0));
+        assert_eq!(single("1_0.0_1"), TokenType::Float(10.01));
+    }
+
+    #[test]
+    fn malformed_numbers_are_words() {
+        for src in &["1__0", "_1", "1_", "0x", "0xG", "0b2", "1e", "1e+", "1.2.3", "."] {
+            assert_eq!(single(src), TokenType::Word(src.to_string()));
+        }
+    }
+
+    #[test]
+    fn out_of_range_literals() {
+        for src in &["9223372036854775808", "-9223372036854775809", "0x1_0000_0000_0000_0000", "1e400", "-1e400"] {
+            let (tokens, errors) = lex(src);
+
+            assert_eq!(errors.len(), 1, "{}", src);
+            assert_eq!(errors[0].get_code(), codes::LITERAL_OUT_OF_RANGE);
+            assert_eq!(tokens[0].kind, TokenType::Word(src.to_string()));
+        }
+    }
+
+    #[test]
+    fn non_ascii_number_like_words() {
+        for src in &["İe", "İe5", "1İe", "0İ", "1eİ", "-İE2"] {
+            let (tokens, errors) = lex(src);
+
+            assert!(errors.is_empty(), "{}", src);
+            assert_eq!(tokens.len(), 1, "{}", src);
+            assert_eq!(tokens[0].kind, TokenType::Word(src.to_string()));
+        }
+    }
+
+    #[test]
+    fn non_ascii_exponent_is_not_a_number() {
+        let (tokens, errors) = lex("[return İe]");
+
+        assert!(errors.is_empty());
+        assert!(tokens.iter().any(|t| t.kind == TokenType::Word("İe".to_owned())));
+
+        let (_, errors) = lex("1e99999");
+
+        assert_eq!(errors[0].get_code(), codes::LITERAL_OUT_OF_RANGE);
+    }
+
+    #[test]
+    fn words_ending_with_question_mark() {
+        let kinds = |src: &str| -> Vec<TokenType> {
+            let (tokens, errors) = lex(src);
+
+            assert!(errors.is_empty(), "{}", src);
+
+            tokens.into_iter().map(|t| t.kind).collect()
+        };
+
+        let word = |w: &str| TokenType::Word(w.to_owned());
+        let control = |c: &str| TokenType::Control(c.to_owned());
+
+        assert_eq!(single("defined?"), word("defined?"));
+        assert_eq!(kinds("[defined? f]"), vec![control("["), word("defined?"), word("f"), control("]")]);
+
+        // [NOTE] `?}` still closes the unquote, and numbers don't take the `?`.
+        //
+        assert_eq!(kinds("{? x?}"), vec![control("{?"), word("x"), control("?}")]);
+        assert_eq!(kinds("1? a??"), vec![TokenType::Int(1), TokenType::Symbol("?".to_owned()), word("a"), TokenType::Symbol("?".to_owned()), TokenType::Symbol("?".to_owned())]);
+    }
+
+    #[test]
+    fn block_comments_take_hash_pipe() {
+        let (tokens, errors) = lex("#|int");
+
+        assert!(tokens.is_empty());
+        assert_eq!(errors[0].get_code(), codes::UNTERMINATED_COMMENT);
+
+        let (tokens, errors) = lex("# |int");
+        let kinds: Vec<TokenType> = tokens.into_iter().map(|t| t.kind).collect();
+
+        assert!(errors.is_empty());
+        assert_eq!(kinds, vec![TokenType::Symbol("#".to_owned()), TokenType::Symbol("|".to_owned()), TokenType::Word("int".to_owned())]);
+
+        let (tokens, errors) = lex("1 #| a #| b |# c |# 2");
+
+        assert!(errors.is_empty());
+        assert_eq!(tokens.len(), 2);
+    }
+
+    #[test]
+    fn string_literals() {
+        assert_eq!(single("\"abc\""), TokenType::Str("abc".to_owned()));
+        assert_eq!(single("\"\""), TokenType::Str(String::new()));
+        assert_eq!(single("\"[a b] ; #| c\""), TokenType::Str("[a b] ; #| c".to_owned()));
+        assert_eq!(single("\"e\u{301}👍🏽\""), TokenType::Str("e\u{301}👍🏽".to_owned()));
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(single(r#""a\nb\tc\rd\0e""#), TokenType::Str("a\nb\tc\rd\0e".to_owned()));
+        assert_eq!(single(r#""\\ \"quoted\"""#), TokenType::Str("\\ \"quoted\"".to_owned()));
+        assert_eq!(single(r#""\u{41}\u{e9}\u{1F600}""#), TokenType::Str("Aé😀".to_owned()));
+    }
+
+    #[test]
+    fn nested_block_comments() {
+        let (tokens, errors) = lex("a #| #| b |# c |# d");
+        let kinds: Vec<TokenType> = tokens.into_iter().map(|t| t.kind).collect();
+
+        assert!(errors.is_empty());
+        assert_eq!(kinds, vec![TokenType::Word("a".to_owned()), TokenType::Word("d".to_owned())]);
+
+        let (_, errors) = lex("#| #| |#");
+
+        assert_eq!(errors[0].get_code(), codes::UNTERMINATED_COMMENT);
+        assert_eq!((errors[0].get_span().start, errors[0].get_span().end), (0, 2));
+    }
+
+    #[test]
+    fn line_comments() {
+        let (tokens, errors) = lex("a ; b [c\nd;e\n;f");
+        let kinds: Vec<TokenType> = tokens.into_iter().map(|t| t.kind).collect();
+
+        assert!(errors.is_empty());
+        assert_eq!(kinds, vec![TokenType::Word("a".to_owned()), TokenType::Word("d".to_owned())]);
+    }
+
+    #[test]
+    fn positions_after_comments() {
+        let (tokens, _) = lex("#| one\ntwo |# a ; three\n  b");
+
+        assert_eq!(tokens[0].span.start_pos, (2, 8));
+        assert_eq!(tokens[1].span.start_pos, (3, 3));
+    }
+
+    #[test]
+    fn comment_tokens() {
+        let symbols: HashSet<&str> = set![":"];
+
+        let kinds = |mut lexer: Lexer| -> Vec<TokenType> { lexer.by_ref().map(|t| t.kind).collect() };
+        let comment = |c: &str| TokenType::Comment(c.to_owned());
+
+        let ws = kinds(Lexer::new_ws_preserving(segment_text("a ;x\n#| y |#"), symbols.clone()));
+
+        assert!(!ws.iter().any(|k| matches!(k, TokenType::Comment(_))));
+
+        let kept = kinds(Lexer::new_comment_preserving(segment_text("a ;x\n#| #| y |# |#"), symbols));
+
+        assert_eq!(kept.iter().filter(|k| matches!(k, TokenType::Comment(_))).cloned().collect::<Vec<_>>(), vec![comment("x"), comment(" #| y |# ")]);
+    }
+
+    fn only_error(src: &str) -> MifulError {
+        let (_, errors) = lex(src);
+
+        assert_eq!(errors.len(), 1, "{}", src);
+
+        errors[0].clone()
+    }
+
+    #[test]
+    fn unterminated_string_error() {
+        let e = only_error("[f \"abc");
+
+        assert_eq!(e.get_code(), codes::UNTERMINATED_STRING);
+        assert_eq!((e.get_span().start, e.get_span().end), (3, 4));
+        assert_eq!(e.get_span().start_pos, (1, 4));
+    }
+
+    #[test]
+    fn invalid_escape_errors() {
+        let e = only_error("\"a\\qb\"");
+
+        assert_eq!(e.get_code(), codes::INVALID_ESCAPE);
+        assert_eq!((e.get_span().start, e.get_span().end), (2, 4));
+
+        let e = only_error("\"\\u{110000}\"");
+
+        assert_eq!(e.get_code(), codes::INVALID_ESCAPE);
+        assert_eq!((e.get_span().start, e.get_span().end), (1, 11));
+    }
+
+    #[test]
+    fn unterminated_symbol_error() {
+        let e = only_error("[f `abc]");
+
+        assert_eq!(e.get_code(), codes::UNTERMINATED_SYMBOL);
+        assert_eq!((e.get_span().start, e.get_span().end), (3, 7));
+    }
+
+    // [NOTE] The lexer never hands such a span over itself, it stops before the `[`.
+    //
+    #[test]
+    fn invalid_literal_error() {
+        let text = segment_text("f a[");
+        let mut lexer = Lexer::new(text, set![":"]);
+
+        let token = lexer.process_literal(vec!["a", "["], 2);
+
+        assert_eq!(token.kind, TokenType::Word("a[".to_owned()));
+
+        let e = &lexer.errors()[0];
+
+        assert_eq!(e.get_code(), codes::INVALID_LITERAL);
+        assert_eq!((e.get_span().start, e.get_span().end), (2, 4));
+    }
+
+    #[test]
+    fn trailing_brace_is_a_control() {
+        let (tokens, errors) = lex("[f {");
+        let kinds: Vec<TokenType> = tokens.into_iter().map(|t| t.kind).collect();
+
+        assert!(errors.is_empty());
+        assert_eq!(kinds.last(), Some(&TokenType::Control("{".to_owned())));
+    }
+
+    #[test]
+    fn stray_unquote_end_error() {
+        let e = only_error("{a ?}");
+
+        assert_eq!(e.get_code(), codes::STRAY_UNQUOTE_END);
+        assert_eq!((e.get_span().start, e.get_span().end), (3, 5));
+        assert_eq!(e.get_span().start_pos, (1, 4));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod span;
 pub mod token;
 pub mod ast;
 
@@ -6,4 +7,5 @@ pub mod utils;
 
 pub mod lexer;
 pub mod parser;
+pub mod diagnostics;
 //pub mod preprocessor;
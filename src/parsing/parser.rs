@@ -3,26 +3,67 @@ use parsing::ast as ast;
 use parsing::token as tok;
 use self::tok::TokenType as tok_type;
 
-use parsing::utils::MifulError;
+use parsing::span::Span;
+use parsing::utils::{ MifulError, Error, Frame, FrameKind, codes };
 
 
 pub struct Parser {
     index: usize,
-    hook_count: usize,
+    // [NOTE] Number of unquotes seen so far in each quote being parsed, innermost last.
+    // Every hook gets numbered within its own quote, in source order.
+    //
+    hook_counts: Vec<usize>,
 
     tokens: Vec<tok::Token>,
+
+    // [NOTE] In recovery mode errors are collected in `errors` and replaced
+    // by error nodes in the tree, instead of stopping the parser.
+    //
+    recover: bool,
+    errors: Vec<MifulError>,
+
+    // [NOTE] Closing tokens of the structures being parsed, innermost last.
+    //
+    open: Vec<String>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<tok::Token>) -> Parser {
         Parser {
             index: 0,
-            hook_count: 0,
+            hook_counts: vec![],
 
-            tokens,
+            tokens: tokens.into_iter().filter(|t| !t.is_trivia()).collect(),
+
+            recover: false,
+            errors: vec![],
+
+            open: vec![],
         }
     }
 
+    pub fn new_recovering(tokens: Vec<tok::Token>) -> Parser {
+        let mut parser = Parser::new(tokens);
+
+        parser.recover = true;
+
+        parser
+    }
+
+    // [NOTE] Parses everything in recovery mode, returning the partial tree
+    // along with every error found, in source order.
+    //
+    pub fn parse_all(tokens: Vec<tok::Token>) -> (Vec<ast::NodeWrapper>, Vec<MifulError>) {
+        let mut parser = Parser::new_recovering(tokens);
+        let tree = parser.by_ref().filter_map(Result::ok).collect();
+
+        let mut errors = parser.errors;
+
+        errors.sort_by_key(|e| e.get_span().start);
+
+        (tree, errors)
+    }
+
     // [AREA] Utilities
     //
     fn step_forward(&mut self) {
@@ -36,6 +77,98 @@ impl Parser {
     fn eof(&self) -> bool {
         self.index >= self.tokens.len()
     }
+
+    // [NOTE] Reports an error at the current structure, returning an error node
+    // in its place when recovering.
+    //
+    fn fail(&mut self, code: &'static str, message: &str, span: Span) -> Option<Result<ast::NodeWrapper, MifulError>> {
+        let e = MifulError::semantic_error(message, span).with_code(code);
+
+        self.recovered(e)
+    }
+
+    // [NOTE] Same as `fail`, for an error already made, like an unterminated structure.
+    //
+    fn recovered(&mut self, e: MifulError) -> Option<Result<ast::NodeWrapper, MifulError>> {
+        if self.recover {
+            let node = ast::NodeWrapper::new_error(&e.get_description(), e.get_span());
+
+            self.errors.push(e);
+
+            Some(Ok(node))
+
+        } else {
+            Some(Err(e))
+        }
+    }
+
+    // [NOTE] Parses the elements of a list, invoke or quote up to and including `closer`,
+    // returning them with the span of the whole structure, starting at `open`.
+    // When recovering, the structure ends early on a closing token that belongs
+    // to an enclosing structure, so the parser resynchronises there. The error
+    // is returned either way, the caller puts an error node in place of the structure.
+    //
+    fn parse_body(&mut self, closer: &str, what: &str, open: Span)
+        -> Result<(Vec<ast::NodeWrapper>, Span), MifulError> {
+
+        let mut values = vec![];
+
+        let mut full_span = open;
+
+        let end = tok_type::Control(closer.to_owned());
+        let unterminated = format!("Unterminated {}!", what);
+
+        self.open.push(closer.to_owned());
+
+        let result = loop {
+            if self.eof() {
+                break Err(MifulError::semantic_error(&unterminated, full_span).with_code(codes::UNTERMINATED_STRUCTURE));
+            }
+
+            let kind = self.get().kind;
+
+            if end == kind {
+                full_span = open.to(&self.get().span);
+
+                self.step_forward();
+
+                break Ok(());
+            }
+
+            if let tok_type::Control(ref s) = kind {
+                if self.recover && self.open.contains(s) {
+                    break Err(MifulError::semantic_error(&unterminated, full_span).with_code(codes::UNTERMINATED_STRUCTURE));
+                }
+            }
+
+            match self.next() {
+                Some(Ok(node)) => {
+                    full_span = open.to(&node.span);
+
+                    values.push(node);
+                },
+
+                Some(Err(e)) => {
+                    let mut new_e = e;
+
+                    new_e.add_frame(Frame::new(FrameKind::Parse, &format!("while parsing {}", what), open));
+
+                    break Err(new_e);
+                },
+
+                None => {
+                    break Err(MifulError::semantic_error(&unterminated, full_span).with_code(codes::UNTERMINATED_STRUCTURE));
+                },
+            }
+        };
+
+        self.open.pop();
+
+        match result {
+            Ok(()) => { Ok((values, full_span)) },
+            Err(e) => { Err(e) },
+        }
+    }
     //
     // [END] Utilities
 }
@@ -49,117 +182,53 @@ impl Iterator for Parser {
             None
 
         } else {
-            let mut hooks = Vec::new();
-
             let token = self.get();
-            let mut last_idx = token.index;
-            let mut pos = token.position;
+            let span = token.span;
 
-            // [TODO] Unquote hooks indexing.
-            //
             match token.kind {
                 tok_type::Control(s) => {
                     match s.as_ref() {
                         "(" => {
-                            let mut values = vec![];
-                            let end_paren = tok_type::Control(")".to_owned());
-
                             self.step_forward();
 
-                            loop {
-                                if self.eof() {
-                                    return Some(Err(MifulError::semantic_error("Unterminated list!", last_idx, pos)));
-                                }
-
-                                if end_paren == self.get().kind {
-                                    break;
-                                }
-
-                                if let Some(result) = self.next() {
-                                    match result {
-                                        Ok(node) => {
-                                            last_idx = node.index;
-                                            pos = node.position;
-
-                                            hooks.extend(node.hooks.clone());
-                                            values.push(node);
-                                        },
+                            match self.parse_body(")", "list", span) {
+                                Ok((values, full_span)) => {
+                                    Some(Ok(ast::NodeWrapper::new_list(values, vec![], full_span)))
+                                },
 
-                                        Err(e) => {
-                                            let mut new_e = e;
-
-                                            new_e.add_layer_top("..while parsing list");
-
-                                            return Some(Err(new_e));
-                                        },
-                                    }
-
-                                } else {
-                                    return Some(Err(MifulError::semantic_error("Unterminated list!", last_idx, pos)));
-                                }
+                                Err(e) => { self.recovered(e) },
                             }
-
-                            self.step_forward();
-
-                            Some(Ok(ast::NodeWrapper::new_list(values, hooks, token.index, token.position)))
                         },
 
                         "[" => {
                             self.step_forward();
 
                             if self.eof() {
-                                return Some(Err(MifulError::semantic_error("Incomplete invoke!", last_idx, pos)))
+                                return self.fail(codes::INCOMPLETE_INVOKE, "Incomplete invoke!", span);
                             }
 
                             let name_node = self.get();
                             let target = name_node.kind;
 
                             if let tok_type::Word(f_name) | tok_type::Symbol(f_name) = target {
-                                let mut with = vec![];
-
-                                let end_bracket = tok_type::Control("]".to_owned());
-
                                 self.step_forward();
 
-                                loop {
-                                    if self.eof() {
-                                        return Some(Err(MifulError::semantic_error("Unterminated invoke!", last_idx, pos)));
-                                    }
-
-                                    if end_bracket == self.get().kind {
-                                        break;
-                                    }
-
-                                    if let Some(result) = self.next() {
-                                        match result {
-                                            Ok(node) => {
-                                                last_idx = node.index;
-                                                pos = node.position;
-
-                                                hooks.extend(node.hooks.clone());
-                                                with.push(node);
-                                            },
-
-                                            Err(e) => {
-                                                let mut new_e = e;
-
-                                                new_e.add_layer_top("..while parsing invoke");
+                                match self.parse_body("]", "invoke", span) {
+                                    Ok((with, full_span)) => {
+                                        Some(Ok(ast::NodeWrapper::new_invoke(f_name, with, vec![], full_span)))
+                                    },
 
-                                                return Some(Err(new_e));
-                                            },
-                                        }
+                                    Err(e) => { self.recovered(e) },
+                                }
 
-                                    } else {
-                                        return Some(Err(MifulError::semantic_error("Unterminated invoke!", last_idx, pos)));
+                            } else {
+                                if self.recover {
+                                    if let Err(e) = self.parse_body("]", "invoke", span) {// [NOTE] Skips to the matching `]`.
+                                        self.errors.push(e);
                                     }
                                 }
 
-                                self.step_forward();
-
-                                Some(Ok(ast::NodeWrapper::new_invoke(f_name, with, hooks, name_node.index, name_node.position)))
-
-                            } else {
-                                Some(Err(MifulError::semantic_error("Invalid function name type!", last_idx, pos)))
+                                self.fail(codes::INVALID_FUNCTION_NAME, "Invalid function name type!", name_node.span)
                             }
                         },
 
@@ -167,103 +236,147 @@ impl Iterator for Parser {
                             self.step_forward();
 
                             if self.eof() {
-                                return Some(Err(MifulError::semantic_error("Incomplete invoke!", last_idx, pos)))
+                                return self.fail(codes::INCOMPLETE_INVOKE, "Incomplete invoke!", span);
                             }
 
-                            let target = self.get().kind;
+                            let name_node = self.get();
+                            let target = name_node.kind;
 
                             if let tok_type::Word(f_name) | tok_type::Symbol(f_name) = target {
-                                let mut with = vec![];
-
-                                let end_brace = tok_type::Control("}".to_owned());
-
                                 self.step_forward();
+                                self.hook_counts.push(0);
 
-                                loop {
-                                    if self.eof() {
-                                        return Some(Err(MifulError::semantic_error("Unterminated quote!", last_idx, pos)));
-                                    }
-
-                                    if end_brace == self.get().kind {
-                                        break;
-                                    }
-
-                                    if let Some(result) = self.next() {
-                                        match result {
-                                            Ok(node) => {
-                                                last_idx = node.index;
-                                                pos = node.position;
+                                let result = self.parse_body("}", "quote", span);
 
-                                                hooks.extend(node.hooks.clone());
-                                                with.push(node);
-                                            },
+                                self.hook_counts.pop();
 
-                                            Err(e) => {
-                                                let mut new_e = e;
-
-                                                new_e.add_layer_top("..while parsing quote");
+                                match result {
+                                    Ok((with, full_span)) => {
+                                        Some(Ok(ast::NodeWrapper::new_quote(f_name, with, vec![], full_span)))
+                                    },
 
-                                                return Some(Err(new_e));
-                                            },
-                                        }
+                                    Err(e) => { self.recovered(e) },
+                                }
 
-                                    } else {
-                                        return Some(Err(MifulError::semantic_error("Unterminated quote!", last_idx, pos)));
+                            } else {
+                                if self.recover {
+                                    if let Err(e) = self.parse_body("}", "quote", span) {// [NOTE] Skips to the matching `}`.
+                                        self.errors.push(e);
                                     }
                                 }
 
-                                self.step_forward();
-
-                                Some(Ok(ast::NodeWrapper::new_quote(f_name, with, hooks, token.index, token.position)))
-
-                            } else {
-                                Some(Err(MifulError::semantic_error("Invalid function name type!", last_idx, pos)))
+                                self.fail(codes::INVALID_FUNCTION_NAME, "Invalid function name type!", name_node.span)
                             }
                         },
 
-                        // [TODO]
+                        // [NOTE] The hook keeps its own expression, so it can be resolved
+                        // wherever the quote ends up. Hooks nested in the expression
+                        // are simply a part of it.
                         //
                         "{?" => {
                             self.step_forward();
 
+                            let v_idx = match self.hook_counts.last_mut() {
+                                Some(count) => { *count += 1; *count - 1 },
+                                None => 0,
+                            };
+
+                            let end_hook = tok_type::Control("?}".to_owned());
+
                             if let Some(result) = self.next() {
                                 match result {
                                     Ok(node) => {
-                                        last_idx = node.index;
-                                        pos = node.position;
+                                        if self.eof() || self.get().kind != end_hook {
+                                            return self.fail(codes::UNTERMINATED_UNQUOTE, "Unterminated unquote!", span.to(&node.span));
+                                        }
 
-                                        // [TODO] Nested hooks?
+                                        let full_span = span.to(&self.get().span);
 
                                         self.step_forward();
-                                        self.hook_count += 1;
 
-                                        Some(Ok(ast::NodeWrapper::new_hook(self.hook_count - 1, vec![node], last_idx, pos)))
+                                        Some(Ok(ast::NodeWrapper::new_hook(v_idx, vec![node], full_span)))
                                     },
 
                                     Err(e) => {
                                         let mut new_e = e;
 
-                                        new_e.add_layer_top("..while parsing unquote");
+                                        new_e.add_frame(Frame::new(FrameKind::Parse, "while parsing unquote", span));
 
                                         Some(Err(new_e))
                                     },
                                 }
 
                             } else {
-                                Some(Err(MifulError::semantic_error("Unterminated unquote!", last_idx, pos)))
+                                self.fail(codes::UNTERMINATED_UNQUOTE, "Unterminated unquote!", span)
                             }
                         },
 
-                        _ => { Some(Err(MifulError::semantic_error(&format!("Unexpected control token: `{}`!", s), last_idx, pos))) }
+                        _ => {
+                            if self.recover {
+                                self.step_forward();
+                            }
+
+                            self.fail(codes::UNEXPECTED_CONTROL, &format!("Unexpected control token: `{}`!", s), span)
+                        }
                     }
                 },
 
-                tok_type::Word(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_word(v, last_idx, pos))) },
-                tok_type::Symbol(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_symbol(v, last_idx, pos))) },
+                tok_type::Word(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_word(v, span))) },
+                tok_type::Symbol(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_symbol(v, span))) },
 
-                tok_type::Int(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_int(v, last_idx, pos))) },
-                tok_type::Float(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_float(v, last_idx, pos))) },
+                tok_type::Int(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_int(v, span))) },
+                tok_type::Float(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_float(v, span))) },
+
+                tok_type::Str(v) => { self.step_forward(); Some(Ok(ast::NodeWrapper::new_str(v, span))) },
+
+                tok_type::Comment(_) => { unreachable!(); },// [UNREACHABLE] Filtered out in `new`.
             }
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::Parser;
+    use parsing::ast::NodeKind;
+    use parsing::lexer::Lexer;
+    use parsing::utils::{ Error, codes, segment_text };
+
+    #[test]
+    fn unterminated_structures_become_error_nodes() {
+        for src in &["(1 2", "[f 1", "{f 1", "[g (1 2]"] {
+            let tokens = Lexer::new(segment_text(src), set![":", "|"]).collect();
+            let (tree, errors) = Parser::parse_all(tokens);
+
+            assert_eq!(errors.len(), 1, "{}", src);
+            assert_eq!(errors[0].get_code(), codes::UNTERMINATED_STRUCTURE, "{}", src);
+
+            let has_error = |kind: &NodeKind| match kind {
+                NodeKind::Error(_) => { true },
+                NodeKind::Invoke{ with, .. } => { with.iter().any(|v| matches!(v.node, NodeKind::Error(_))) },
+                _ => { false },
+            };
+
+            assert!(tree.iter().any(|n| has_error(&n.node)), "{}", src);
+        }
+    }
+
+    #[test]
+    fn errors_come_in_source_order() {
+        // [NOTE] The unclosed list is found while skipping the invoke, before
+        // the invalid name gets reported.
+        //
+        let src = "[1 (2]";
+        let tokens = Lexer::new(segment_text(src), set![":", "|"]).collect();
+        let (_, errors) = Parser::parse_all(tokens);
+
+        let starts: Vec<usize> = errors.iter().map(|e| e.get_span().start).collect();
+        let mut sorted = starts.clone();
+
+        sorted.sort();
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(starts, sorted);
+    }
+}
@@ -0,0 +1,185 @@
+// [NOTE] A range of the source, the end is exclusive.
+//
+// Indices count graphemes (the way the lexer reads the source), byte offsets
+// count bytes of the original string, positions are (line, column) counted
+// from 1, with columns in graphemes.
+//
+// [NOTE] The default span (everything zero) stands for an unknown location.
+//
+// [NOTE] `file` tells apart the sources a driver has read (files and REPL inputs),
+// counted from 1, and 0 if unknown. It's set by the driver, not the lexer.
+//
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Span {
+    pub file: usize,
+
+    pub start: usize,
+    pub end: usize,
+
+    pub start_byte: usize,
+    pub end_byte: usize,
+
+    pub start_pos: (usize, usize),
+    pub end_pos: (usize, usize),
+}
+
+impl Span {
+    // [NOTE] Returns the span running from the start of `self` to the end of `other`.
+    //
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            file: self.file,
+
+            start: self.start,
+            end: other.end,
+
+            start_byte: self.start_byte,
+            end_byte: other.end_byte,
+
+            start_pos: self.start_pos,
+            end_pos: other.end_pos,
+        }
+    }
+}
+
+
+// [NOTE] Byte offsets and positions of every grapheme of a segmented source,
+// with one extra entry for the end of input.
+//
+#[derive(Debug)]
+pub struct SourceMap {
+    offsets: Vec<usize>,
+    positions: Vec<(usize, usize)>,
+}
+
+impl SourceMap {
+    pub fn new(source: &Vec<&str>) -> SourceMap {
+        let mut offsets = Vec::with_capacity(source.len() + 1);
+        let mut positions = Vec::with_capacity(source.len() + 1);
+
+        let mut offset = 0;
+        let mut position = (1, 1);
+
+        for g in source {
+            offsets.push(offset);
+            positions.push(position);
+
+            offset += g.len();
+
+            if *g == "\n" || *g == "\r\n" {
+                position = (position.0 + 1, 1);
+
+            } else {
+                position.1 += 1;
+            }
+        }
+
+        offsets.push(offset);
+        positions.push(position);
+
+        SourceMap {
+            offsets,
+            positions,
+        }
+    }
+
+    pub fn span(&self, start: usize, end: usize) -> Span {
+        Span {
+            file: 0,
+
+            start,
+            end,
+
+            start_byte: self.offsets[start],
+            end_byte: self.offsets[end],
+
+            start_pos: self.positions[start],
+            end_pos: self.positions[end],
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{ Span, SourceMap };
+    use parsing::lexer::Lexer;
+    use parsing::parser::Parser;
+    use parsing::ast::NodeKind;
+    use parsing::utils::segment_text;
+
+    use std::collections::HashSet;
+
+    #[test]
+    fn lines_and_columns() {
+        let source = segment_text("ab\ncd\r\ne");
+        let map = SourceMap::new(&source);
+
+        // [NOTE] `\r\n` is a single grapheme.
+        //
+        assert_eq!(map.span(0, 1).start_pos, (1, 1));
+        assert_eq!(map.span(2, 3).start_pos, (1, 3));
+        assert_eq!(map.span(3, 4).start_pos, (2, 1));
+        assert_eq!(map.span(5, 6).start_pos, (2, 3));
+        assert_eq!(map.span(6, 7).start_pos, (3, 1));
+
+        // [NOTE] The end of input has an entry, too.
+        //
+        assert_eq!(map.span(7, 7).end_pos, (3, 2));
+    }
+
+    #[test]
+    fn byte_offsets_of_wide_graphemes() {
+        let source = segment_text("ž🙂e\u{301}x");
+        let map = SourceMap::new(&source);
+
+        let span = map.span(1, 3);
+
+        assert_eq!((span.start, span.end), (1, 3));
+        assert_eq!((span.start_byte, span.end_byte), (2, 9));
+        assert_eq!((span.start_pos, span.end_pos), ((1, 2), (1, 4)));
+
+        assert_eq!(map.span(3, 4).start_byte, 9);
+    }
+
+    #[test]
+    fn to_joins_spans() {
+        let map = SourceMap::new(&segment_text("abc\ndef"));
+
+        let joint = map.span(0, 1).to(&map.span(5, 6));
+
+        assert_eq!((joint.start, joint.end), (0, 6));
+        assert_eq!((joint.start_pos, joint.end_pos), ((1, 1), (2, 3)));
+    }
+
+    #[test]
+    fn nodes_cover_their_brackets() {
+        let symbols: HashSet<&str> = [":", "@", "&", "|", "#", "~", "?", "\\"].iter().cloned().collect();
+        let source = "[+ 1\n  [* 2 3]]";
+
+        let tokens: Vec<_> = Lexer::new(segment_text(source), symbols).collect();
+        let (tree, errors) = Parser::parse_all(tokens);
+
+        assert!(errors.is_empty());
+
+        let outer = &tree[0];
+
+        assert_eq!((outer.span.start_byte, outer.span.end_byte), (0, source.len()));
+        assert_eq!((outer.span.start_pos, outer.span.end_pos), ((1, 1), (2, 11)));
+
+        if let NodeKind::Invoke{ with, .. } = &outer.node {
+            let inner = &with[1];
+
+            assert_eq!(&source[inner.span.start_byte .. inner.span.end_byte], "[* 2 3]");
+            assert_eq!(inner.span.start_pos, (2, 3));
+
+        } else {
+            panic!("Expected an invoke, got {}", outer.node);
+        }
+    }
+
+    #[test]
+    fn default_span_is_unknown() {
+        assert_eq!(Span::default().start_pos, (0, 0));
+    }
+}
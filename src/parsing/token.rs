@@ -1,3 +1,6 @@
+use parsing::span::Span;
+
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     Control(String),
@@ -5,75 +8,76 @@ pub enum TokenType {
     Word(String),
     Int(i64),
     Float(f64),
-    Symbol(String)
+    Symbol(String),
+    Str(String),
+
+    Comment(String),// [NOTE] Trivia, never reaches the parser.
 }
 
 #[derive(Debug, Clone)]
 pub struct Token {
     pub kind: TokenType,
 
-    pub position: (usize, usize),
-    pub index: usize,
-    pub span: usize,
+    pub span: Span,
 }
 
 impl Token {
-    // pub fn new_undefined(position: (usize, usize), index: usize, span: usize) -> Token {
-    //     Token {
-    //         kind: TokenType::Undefined,
-    //
-    //         position,
-    //         index,
-    //         span,
-    //     }
-    // }
-
-    pub fn new_control(sym: &str, position: (usize, usize), index: usize, span: usize) -> Token {
+    pub fn new_control(sym: &str, span: Span) -> Token {
         Token {
             kind: TokenType::Control(sym.to_owned()),
 
-            position,
-            index,
             span,
         }
     }
 
-    pub fn new_word(val: &str, position: (usize, usize), index: usize, span: usize) -> Token {
+    pub fn new_word(val: &str, span: Span) -> Token {
         Token {
             kind: TokenType::Word(val.to_owned()),
 
-            position,
-            index,
             span,
         }
     }
 
-    pub fn new_symbol(sym: &str, position: (usize, usize), index: usize, span: usize) -> Token {
+    pub fn new_symbol(sym: &str, span: Span) -> Token {
         Token {
             kind: TokenType::Symbol(sym.to_owned()),
 
-            position,
-            index,
             span,
         }
     }
 
-    pub fn new_int(val: i64, position: (usize, usize), index: usize, span: usize) -> Token {
+    pub fn new_int(val: i64, span: Span) -> Token {
         Token {
             kind: TokenType::Int(val),
 
-            position,
-            index,
             span,
         }
     }
 
-    pub fn new_float(val: f64, position: (usize, usize), index: usize, span: usize) -> Token {
+    pub fn new_str(val: &str, span: Span) -> Token {
+        Token {
+            kind: TokenType::Str(val.to_owned()),
+
+            span,
+        }
+    }
+
+    pub fn new_comment(text: &str, span: Span) -> Token {
+        Token {
+            kind: TokenType::Comment(text.to_owned()),
+
+            span,
+        }
+    }
+
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, TokenType::Comment(_))
+    }
+
+    pub fn new_float(val: f64, span: Span) -> Token {
         Token {
             kind: TokenType::Float(val),
 
-            position,
-            index,
             span,
         }
     }
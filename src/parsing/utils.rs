@@ -1,36 +1,24 @@
 extern crate unicode_segmentation;
 use self::unicode_segmentation::UnicodeSegmentation;
 
-use std::cmp;
-use std::io::Write;
-use std::collections::{ HashMap, HashSet };
+use parsing::span::Span;
+use parsing::diagnostics::{ Label, Renderer };
 
-
-const ERR_CONTEXT_LEN: usize = 10;
+use std::fmt::{ self, Formatter, Display };
+use std::io::{ self, Write };
 
 
 pub fn segment_text(input: &str) -> Vec<&str> {
     UnicodeSegmentation::graphemes(input, true).collect::<Vec<&str>>()
 }
 
-pub fn input<'a>() -> String {
-    let mut in_s = String::new();
-
-    let stdin = std::io::stdin();
-    let mut stdout = std::io::stdout();
-
-    let _ = stdout.flush();
-    stdin.read_line(&mut in_s).expect("Failed to read input!");
-
-    in_s
-}
-
 
 #[macro_export]
 macro_rules! set {
     ( $( $x:expr ),* ) => {
         {
-            let mut temp_set = HashSet::new();
+            #[allow(unused_mut)]
+            let mut temp_set = ::std::collections::HashSet::new();
 
             $(
                 temp_set.insert($x);
@@ -45,7 +33,8 @@ macro_rules! set {
 macro_rules! map {
     ( $( $k:expr => $v:expr ),* ) => {
         {
-            let mut temp_map = HashMap::new();
+            #[allow(unused_mut)]
+            let mut temp_map = ::std::collections::HashMap::new();
 
             $(
                 temp_map.insert($k, $v);
@@ -59,7 +48,8 @@ macro_rules! map {
     //
     ( $( $k:expr => $v:expr ),* , ) => {
         {
-            let mut temp_map = HashMap::new();
+            #[allow(unused_mut)]
+            let mut temp_map = ::std::collections::HashMap::new();
 
             $(
                 temp_map.insert($k, $v);
@@ -71,63 +61,201 @@ macro_rules! map {
 }
 
 
+// [NOTE] Stable error codes, for tools that match on errors instead of messages.
+// Codes never get renumbered or reused, new ones are only appended. Errors
+// without a specific code get the general code of their kind.
+//
+pub mod codes {
+    pub const PARSE: &str = "P000";
+    pub const UNTERMINATED_STRING: &str = "P001";
+    pub const INVALID_ESCAPE: &str = "P002";
+    pub const UNTERMINATED_COMMENT: &str = "P003";
+    pub const LITERAL_OUT_OF_RANGE: &str = "P004";
+    pub const UNTERMINATED_SYMBOL: &str = "P005";
+    pub const INVALID_LITERAL: &str = "P006";
+    pub const STRAY_UNQUOTE_END: &str = "P007";
+
+    pub const SEMANTIC: &str = "S000";
+    pub const UNTERMINATED_STRUCTURE: &str = "S001";
+    pub const INCOMPLETE_INVOKE: &str = "S002";
+    pub const INVALID_FUNCTION_NAME: &str = "S003";
+    pub const UNEXPECTED_CONTROL: &str = "S004";
+    pub const UNTERMINATED_UNQUOTE: &str = "S005";
+
+    pub const RUNTIME: &str = "R000";
+    pub const PARAM_COUNT: &str = "R001";
+    pub const PARAM_TYPE: &str = "R002";
+    pub const TYPE_SIGNATURE: &str = "R003";
+    pub const DIVISION_BY_ZERO: &str = "R004";
+    pub const UNDEFINED_CONSTANT: &str = "R005";
+    pub const NO_MATCHING_FUNCTION: &str = "R006";
+    pub const UNDEFINED_FUNCTION: &str = "R007";
+    pub const BUILTIN_FUNCTION: &str = "R008";
+    pub const SYNTAX_ERROR: &str = "R009";
+    pub const INTEGER_OVERFLOW: &str = "R010";
+    pub const HOST_FUNCTION: &str = "R011";
+    pub const END_OF_INPUT: &str = "R012";
+    pub const CONSOLE: &str = "R013";
+    pub const MODULE_NOT_FOUND: &str = "R014";
+    pub const IMPORT_CYCLE: &str = "R015";
+    pub const UNDEFINED_EXPORT: &str = "R016";
+    pub const AMBIGUOUS_OVERLOAD: &str = "R017";
+    pub const RANGE_TOO_LARGE: &str = "R018";
+    pub const INVALID_OBJECT: &str = "R019";
+    pub const DUPLICATE_FIELD: &str = "R020";
+    pub const UNDECLARED_STRUCTURE: &str = "R021";
+    pub const FIELD_COUNT: &str = "R022";
+    pub const FIELD_TYPE: &str = "R023";
+    pub const UNKNOWN_FIELD: &str = "R024";
+    pub const INDEX_OUT_OF_RANGE: &str = "R025";
+    pub const FORMAT_VALUES: &str = "R026";
+    pub const INVALID_CONVERSION: &str = "R027";
+    pub const INCOMPARABLE: &str = "R028";
+    pub const INVALID_BINDING: &str = "R029";
+    pub const MISPLACED_EXPORT: &str = "R030";
+    pub const DIFFERENT_OBJECTS: &str = "R031";
+    pub const EMPTY_LIST: &str = "R032";
+}
+
+
 pub trait Error {
+    // [NOTE] Writes the rendered error into `out`, never panics.
+    //
+    fn report<W: Write>(&self, out: &mut W, renderer: &Renderer) -> io::Result<()> {
+        write!(out, "{}", renderer.render(self))
+    }
+
     fn print_err(&self) {
-        let source = self.get_source();
+        let _ = self.report(&mut io::stdout(), &Renderer::for_stdout());
+    }
 
-        let start_pos = cmp::max(self.get_index(), ERR_CONTEXT_LEN) - ERR_CONTEXT_LEN;
-        let end_pos = cmp::min(self.get_index() + ERR_CONTEXT_LEN, source.len());
+    // [NOTE] Panics after printing the error, only meant for quick scripts.
+    // A host should `report` the error and go on instead.
+    //
+    fn throw_err(&self) {
+        self.print_err();
 
-        let mut extra_offset = 0;
+        println!("\n");
 
-        let src_line = source[start_pos .. end_pos]
-            .to_owned()
-            .iter()
-            .map(|s| match s.as_ref() {
-                "\n" => { extra_offset += 1; "\\n" },
-                "\t" => { extra_offset += 1; "\\t" },
-                "\r" => { extra_offset += 1; "\\r" },
+        panic!("{} error occurred!", self.get_kind());
+    }
+
+    fn add_frame(&mut self, frame: Frame);
 
-                _ => { s },
-            })
-            .collect::<Vec<&str>>()
-            .join("");
+    fn add_label(&mut self, span: Span, message: &str);
+    fn set_code(&mut self, code: &'static str);
 
-        let pos = self.get_position();
+    fn get_kind(&self) -> &str;
+    fn get_code(&self) -> &'static str;
+    fn get_span(&self) -> Span;
+    fn get_source(&self) -> &Vec<String>;
 
-        println!("{} error occurred at {}:{}\n", self.get_kind(), pos.0, pos.1);
+    // [NOTE] The message itself, without context.
+    //
+    fn get_description(&self) -> String;
 
-        println!("[source-string]:");
-        println!("... {} ...", src_line);
+    // [NOTE] Outermost frame first.
+    //
+    fn get_context(&self) -> &Vec<Frame>;
+    fn get_labels(&self) -> &Vec<Label>;
 
-        print!("    ");
+    // [NOTE] The calls the error went through, innermost first.
+    //
+    fn get_backtrace(&self) -> Vec<&Frame> {
+        self.get_context()
+            .iter()
+            .rev()
+            .filter(|frame| frame.is_call())
+            .collect()
+    }
 
-        let tilde_count = extra_offset + (end_pos - start_pos) / 2;
+    // [NOTE] The message below its context layers, each nested layer indented by `| `.
+    //
+    fn get_message(&self) -> String {
+        let context = self.get_context();
+        let mut lines = vec![];
 
-        for _ in 0..tilde_count {
-            print!("~");
+        for (depth, frame) in context.iter().enumerate() {
+            for line in format!("..{}", frame).split('\n') {
+                lines.push(format!("{}{}", "| ".repeat(depth), line));
+            }
         }
 
-        println!("^ -- here\n");
+        for line in self.get_description().split('\n') {
+            lines.push(format!("{}{}", "| ".repeat(context.len()), line));
+        }
 
-        println!("{}", self.get_message());
+        lines.join("\n")
     }
+}
 
-    fn throw_err(&self) {
-        self.print_err();
 
-        println!("\n");
+// [NOTE] What the interpreter was doing when an error passed through it.
+//
+#[derive(Clone, Debug, PartialEq)]
+pub enum FrameKind {
+    Call,// Calling the function `name`.
+    Builtin,// Running a quote from the built-in `name`, like `map`.
+    Argument(usize),// Evaluating the argument (counted from 1) of an invoke of `name`.
+    Evaluate,// Evaluating a quote, unquote or list.
+    Define,// Defining the function or structure `name`.
+    Bind,// Binding the constant `name`.
+    Parse,// Parsing the source.
+    Import,// Running the file `name` imported by a script.
+    Other,
+}
+
+// [NOTE] One layer of error context. The span points to the node being
+// processed, the call site for calls; the default span if there's none.
+//
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub kind: FrameKind,
+    pub name: Option<String>,
+    pub span: Span,
 
-        panic!(format!("{} error occurred!", self.get_kind()));
+    pub message: String,
+}
+
+impl Frame {
+    pub fn new(kind: FrameKind, message: &str, span: Span) -> Frame {
+        Frame {
+            kind,
+            name: None,
+            span,
+
+            message: message.to_owned(),
+        }
     }
 
-    fn add_layer_top(&mut self, &str);
+    pub fn named(kind: FrameKind, name: &str, message: &str, span: Span) -> Frame {
+        Frame {
+            kind,
+            name: Some(name.to_owned()),
+            span,
 
-    fn get_kind(&self) -> &str;
-    fn get_index(&self) -> usize;
-    fn get_message(&self) -> String;
-    fn get_source(&self) -> &Vec<String>;
-    fn get_position(&self) -> (usize, usize);
+            message: message.to_owned(),
+        }
+    }
+
+    // [NOTE] Calls make up the backtrace, the rest are notes.
+    //
+    pub fn is_call(&self) -> bool {
+        self.kind == FrameKind::Call || self.kind == FrameKind::Builtin
+    }
+}
+
+// [NOTE] E.g. `while calling function fact at 4:3`.
+//
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.span.start_pos.0 > 0 {
+            write!(f, "{} at {}:{}", self.message, self.span.start_pos.0, self.span.start_pos.1)
+
+        } else {
+            write!(f, "{}", self.message)
+        }
+    }
 }
 
 
@@ -135,11 +263,14 @@ pub trait Error {
 //
 #[derive(Clone, Debug)]
 pub struct ParseError {
-    index: usize,
-    position: (usize, usize),
+    span: Span,
     source: Vec<String>,
 
     message: Vec<String>,
+    context: Vec<Frame>,
+    labels: Vec<Label>,
+
+    code: &'static str,
 }
 
 // [NOTE] Semantic errors get thrown by parser, which knows
@@ -149,10 +280,14 @@ pub struct ParseError {
 //
 #[derive(Clone, Debug)]
 pub struct SemanticError {
-    index: usize,
-    position: (usize, usize),
+    span: Span,
 
     message: Vec<String>,
+    context: Vec<Frame>,
+    labels: Vec<Label>,
+
+    code: &'static str,
+
     source: Vec<String>,
 }
 
@@ -160,86 +295,72 @@ pub struct SemanticError {
 //
 #[derive(Clone, Debug)]
 pub struct RuntimeError {
-    index: usize,
-    position: (usize, usize),
+    span: Span,
     source: Vec<String>,
 
     message: Vec<String>,
+    context: Vec<Frame>,
+    labels: Vec<Label>,
+
+    code: &'static str,
 }
 
+// [NOTE] Boxed, so a `Result` carrying an error stays as small as a pointer.
+//
 #[derive(Clone, Debug)]
 pub enum MifulError {
-    Parsing(ParseError),
-    Semantics(SemanticError),
-    Runtime(RuntimeError),
+    Parsing(Box<ParseError>),
+    Semantics(Box<SemanticError>),
+    Runtime(Box<RuntimeError>),
 }
 
 
 impl MifulError {
-    pub fn parse_error(message: &str, source: &Vec<String>, index: usize, position: (usize, usize)) -> MifulError {
+    pub fn parse_error(message: &str, source: &[String], span: Span) -> MifulError {
         MifulError::Parsing(
-            ParseError::new(message, source.to_vec(), index, position)
+            Box::new(ParseError::new(message, source.to_vec(), span))
         )
     }
 
-    pub fn semantic_error(message: &str, index: usize, position: (usize, usize)) -> MifulError {
+    pub fn semantic_error(message: &str, span: Span) -> MifulError {
         MifulError::Semantics(
-            SemanticError::new(message, index, position)
+            Box::new(SemanticError::new(message, span))
         )
     }
 
-    pub fn runtime_error(message: &str, source: &Vec<String>, index: usize, position: (usize, usize)) -> MifulError {
+    pub fn runtime_error(message: &str, source: &[String], span: Span) -> MifulError {
         MifulError::Runtime(
-            RuntimeError::new(message, source.to_vec(), index, position)
+            Box::new(RuntimeError::new(message, source.to_vec(), span))
         )
     }
 
 
+    pub fn with_code(self, code: &'static str) -> MifulError {
+        let mut new_e = self;
+
+        new_e.set_code(code);
+
+        new_e
+    }
+
+
     pub fn from_parse_error(e: ParseError) -> MifulError {
-        MifulError::Parsing(e)
+        MifulError::Parsing(Box::new(e))
     }
 
     pub fn from_semantic_error(e: SemanticError) -> MifulError {
-        MifulError::Semantics(e)
+        MifulError::Semantics(Box::new(e))
     }
 
     pub fn from_runtime_error(e: RuntimeError) -> MifulError {
-        MifulError::Runtime(e)
+        MifulError::Runtime(Box::new(e))
     }
 
 
-    pub fn add_layer_top(&mut self, message: &str) {
-        *self = match &self {
-            MifulError::Parsing(e) => {
-                let mut new_e = e.clone();
-
-                new_e.add_layer_top(message);
-
-                MifulError::from_parse_error(new_e)
-            },
-
-            MifulError::Semantics(e) => {
-                let mut new_e = e.clone();
-
-                new_e.add_layer_top(message);
-
-                MifulError::from_semantic_error(new_e)
-            },
-
-            MifulError::Runtime(e) => {
-                let mut new_e = e.clone();
-
-                new_e.add_layer_top(message);
-
-                MifulError::from_runtime_error(new_e)
-            },
-        }
-    }
-
-    pub fn supply_source(&mut self, src: &Vec<String>) {
+    pub fn supply_source(&mut self, src: &[String]) {
         *self = match &self {
             MifulError::Semantics(e) => {
-                let mut new_e = e.clone();
+                let mut new_e = (**e).clone();
 
                 new_e.supply_source(src);
 
@@ -252,55 +373,75 @@ impl MifulError {
 }
 
 impl Error for MifulError {
-    fn add_layer_top(&mut self, message: &str) {
-        *self = match &self {
-            MifulError::Parsing(e) => {
-                let mut new_e = e.clone();
-
-                new_e.add_layer_top(message);
-
-                MifulError::from_parse_error(new_e)
-            },
-
-            MifulError::Semantics(e) => {
-                let mut new_e = e.clone();
+    fn add_frame(&mut self, frame: Frame) {
+        match self {
+            MifulError::Parsing(e) => e.add_frame(frame),
+            MifulError::Semantics(e) => e.add_frame(frame),
+            MifulError::Runtime(e) => e.add_frame(frame),
+        }
+    }
 
-                new_e.add_layer_top(message);
+    fn get_kind(&self) -> &str {
+        match &self {
+            MifulError::Parsing(e) => e.get_kind(),
+            MifulError::Semantics(e) => e.get_kind(),
+            MifulError::Runtime(e) => e.get_kind(),
+        }
+    }
 
-                MifulError::from_semantic_error(new_e)
-            },
+    fn get_span(&self) -> Span {
+        match &self {
+            MifulError::Parsing(e) => e.get_span(),
+            MifulError::Semantics(e) => e.get_span(),
+            MifulError::Runtime(e) => e.get_span(),
+        }
+    }
 
-            MifulError::Runtime(e) => {
-                let mut new_e = e.clone();
+    fn add_label(&mut self, span: Span, message: &str) {
+        match self {
+            MifulError::Parsing(e) => e.add_label(span, message),
+            MifulError::Semantics(e) => e.add_label(span, message),
+            MifulError::Runtime(e) => e.add_label(span, message),
+        }
+    }
 
-                new_e.add_layer_top(message);
+    fn set_code(&mut self, code: &'static str) {
+        match self {
+            MifulError::Parsing(e) => e.set_code(code),
+            MifulError::Semantics(e) => e.set_code(code),
+            MifulError::Runtime(e) => e.set_code(code),
+        }
+    }
 
-                MifulError::from_runtime_error(new_e)
-            },
-        };
+    fn get_code(&self) -> &'static str {
+        match &self {
+            MifulError::Parsing(e) => e.get_code(),
+            MifulError::Semantics(e) => e.get_code(),
+            MifulError::Runtime(e) => e.get_code(),
+        }
     }
 
-    fn get_kind(&self) -> &str {
+    fn get_description(&self) -> String {
         match &self {
-            MifulError::Parsing(e) => e.get_kind(),
-            MifulError::Semantics(e) => e.get_kind(),
-            MifulError::Runtime(e) => e.get_kind(),
+            MifulError::Parsing(e) => e.get_description(),
+            MifulError::Semantics(e) => e.get_description(),
+            MifulError::Runtime(e) => e.get_description(),
         }
     }
 
-    fn get_index(&self) -> usize {
+    fn get_context(&self) -> &Vec<Frame> {
         match &self {
-            MifulError::Parsing(e) => e.get_index(),
-            MifulError::Semantics(e) => e.get_index(),
-            MifulError::Runtime(e) => e.get_index(),
+            MifulError::Parsing(e) => e.get_context(),
+            MifulError::Semantics(e) => e.get_context(),
+            MifulError::Runtime(e) => e.get_context(),
         }
     }
 
-    fn get_message(&self) -> String {
+    fn get_labels(&self) -> &Vec<Label> {
         match &self {
-            MifulError::Parsing(e) => e.get_message(),
-            MifulError::Semantics(e) => e.get_message(),
-            MifulError::Runtime(e) => e.get_message(),
+            MifulError::Parsing(e) => e.get_labels(),
+            MifulError::Semantics(e) => e.get_labels(),
+            MifulError::Runtime(e) => e.get_labels(),
         }
     }
 
@@ -311,161 +452,255 @@ impl Error for MifulError {
             MifulError::Runtime(e) => e.get_source(),
         }
     }
-
-    fn get_position(&self) -> (usize, usize) {
-        match &self {
-            MifulError::Parsing(e) => e.get_position(),
-            MifulError::Semantics(e) => e.get_position(),
-            MifulError::Runtime(e) => e.get_position(),
-        }
-    }
 }
 
 
 impl ParseError {
-    pub fn new(message: &str, source: Vec<String>, index: usize, position: (usize, usize)) -> ParseError {
+    pub fn new(message: &str, source: Vec<String>, span: Span) -> ParseError {
         ParseError {
-            index,
-            position,
+            span,
             source,
 
             message:
                 message
-                    .clone()
                     .split('\n')
                     .map(ToOwned::to_owned)
                     .collect(),
+
+            context: vec![],
+            labels: vec![],
+
+            code: codes::PARSE,
         }
     }
 }
 
 impl Error for ParseError {
-    fn add_layer_top(&mut self, message: &str) {
-        let lines = message.clone().split('\n').map(ToOwned::to_owned);
+    fn add_frame(&mut self, frame: Frame) {
+        self.context.insert(0, frame);
+    }
 
-        self.message = self.message.iter().map(|s| format!("| {}", s)).collect();
+    fn add_label(&mut self, span: Span, message: &str) {
+        self.labels.push(Label::new(span, message));
+    }
 
-        self.message.splice(0..0, lines);
+    fn set_code(&mut self, code: &'static str) {
+        self.code = code;
     }
 
     fn get_kind(&self) -> &str {
         "Parse"
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_code(&self) -> &'static str {
+        self.code
     }
 
-    fn get_message(&self) -> String {
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn get_description(&self) -> String {
         self.message.join("\n")
     }
 
-    fn get_source(&self) -> &Vec<String> {
-        &self.source
+    fn get_context(&self) -> &Vec<Frame> {
+        &self.context
     }
 
-    fn get_position(&self) -> (usize, usize) {
-        self.position
+    fn get_labels(&self) -> &Vec<Label> {
+        &self.labels
+    }
+
+    fn get_source(&self) -> &Vec<String> {
+        &self.source
     }
 }
 
 
 impl SemanticError {
-    pub fn new(message: &str, index: usize, position: (usize, usize)) -> SemanticError {
+    pub fn new(message: &str, span: Span) -> SemanticError {
         SemanticError {
             message:
                 message
-                    .clone()
                     .split('\n')
                     .map(ToOwned::to_owned)
                     .collect(),
 
-            position,
-            index,
+            context: vec![],
+            labels: vec![],
+
+            code: codes::SEMANTIC,
+
+            span,
 
             source: vec![],
         }
     }
 
-    pub fn supply_source(&mut self, src: &Vec<String>) {
+    pub fn supply_source(&mut self, src: &[String]) {
         self.source = src.to_vec();
     }
 }
 
 impl Error for SemanticError {
-    fn add_layer_top(&mut self, message: &str) {
-        let lines = message.clone().split('\n').map(ToOwned::to_owned);
+    fn add_frame(&mut self, frame: Frame) {
+        self.context.insert(0, frame);
+    }
 
-        self.message = self.message.iter().map(|s| format!("| {}", s)).collect();
+    fn add_label(&mut self, span: Span, message: &str) {
+        self.labels.push(Label::new(span, message));
+    }
 
-        self.message.splice(0..0, lines);
+    fn set_code(&mut self, code: &'static str) {
+        self.code = code;
     }
 
     fn get_kind(&self) -> &str {
         "Semantic"
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_code(&self) -> &'static str {
+        self.code
     }
 
-    fn get_message(&self) -> String {
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn get_description(&self) -> String {
         self.message.join("\n")
     }
 
-    fn get_source(&self) -> &Vec<String> {
-        &self.source
+    fn get_context(&self) -> &Vec<Frame> {
+        &self.context
     }
 
-    fn get_position(&self) -> (usize, usize) {
-        self.position
+    fn get_labels(&self) -> &Vec<Label> {
+        &self.labels
+    }
+
+    fn get_source(&self) -> &Vec<String> {
+        &self.source
     }
 }
 
 
 impl RuntimeError {
-    pub fn new(message: &str, source: Vec<String>, index: usize, position: (usize, usize)) -> RuntimeError {
+    pub fn new(message: &str, source: Vec<String>, span: Span) -> RuntimeError {
         RuntimeError {
-            index,
-            position,
+            span,
             source,
 
             message:
                 message
-                    .clone()
                     .split('\n')
                     .map(ToOwned::to_owned)
                     .collect(),
+
+            context: vec![],
+            labels: vec![],
+
+            code: codes::RUNTIME,
         }
     }
 }
 
 impl Error for RuntimeError {
-    fn add_layer_top(&mut self, message: &str) {
-        let lines = message.clone().split('\n').map(ToOwned::to_owned);
+    fn add_frame(&mut self, frame: Frame) {
+        self.context.insert(0, frame);
+    }
 
-        self.message = self.message.iter().map(|s| format!("| {}", s)).collect();
+    fn add_label(&mut self, span: Span, message: &str) {
+        self.labels.push(Label::new(span, message));
+    }
 
-        self.message.splice(0..0, lines);
+    fn set_code(&mut self, code: &'static str) {
+        self.code = code;
     }
 
     fn get_kind(&self) -> &str {
         "Runtime"
     }
 
-    fn get_index(&self) -> usize {
-        self.index
+    fn get_code(&self) -> &'static str {
+        self.code
     }
 
-    fn get_message(&self) -> String {
+    fn get_span(&self) -> Span {
+        self.span
+    }
+
+    fn get_description(&self) -> String {
         self.message.join("\n")
     }
 
+    fn get_context(&self) -> &Vec<Frame> {
+        &self.context
+    }
+
+    fn get_labels(&self) -> &Vec<Label> {
+        &self.labels
+    }
+
     fn get_source(&self) -> &Vec<String> {
         &self.source
     }
+}
+
 
-    fn get_position(&self) -> (usize, usize) {
-        self.position
+// [NOTE] One line for logs and hosts, e.g.
+//    Runtime error R004 at 3:5: Division by zero!
+// ..followed by a line for every context frame, innermost first.
+//
+fn describe<E: Error + ?Sized>(e: &E, f: &mut Formatter) -> fmt::Result {
+    let pos = e.get_span().start_pos;
+
+    let result = if pos.0 > 0 {
+        write!(f, "{} error {} at {}:{}: {}", e.get_kind(), e.get_code(), pos.0, pos.1, e.get_description())
+
+    } else {
+        write!(f, "{} error {}: {}", e.get_kind(), e.get_code(), e.get_description())
+    };
+
+    if result.is_err() {
+        return result;
+    }
+
+    for frame in e.get_context().iter().rev() {
+        if let Err(err) = write!(f, "\n    {}", frame) {
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for MifulError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        describe(self, f)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        describe(self, f)
     }
 }
+
+impl Display for SemanticError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        describe(self, f)
+    }
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        describe(self, f)
+    }
+}
+
+impl ::std::error::Error for MifulError {}
+impl ::std::error::Error for ParseError {}
+impl ::std::error::Error for SemanticError {}
+impl ::std::error::Error for RuntimeError {}
@@ -0,0 +1,322 @@
+use driver::Driver;
+use convert::is_nil;
+use parsing::diagnostics::Renderer;
+use parsing::lexer::Lexer;
+use parsing::parser::Parser;
+use parsing::utils::{ MifulError, Error, codes, segment_text };
+
+use std::fs;
+
+
+/*
+
+# REPL
+
+    Reads expressions from the console of a driver and prints their results,
+keeping the functions and structures defined so far. An expression with an
+unclosed bracket, string or comment continues on the next line.
+
+    Lines starting with `:` are meta-commands:
+
+* :help
+    > lists the meta-commands
+
+* :functions
+    > lists the signatures of every defined function
+
+* :type (expression)
+    > evaluates (expression) and prints the type of every value
+
+* :load (file)
+    > evaluates (file) like it was entered
+
+* :reset
+    > forgets every defined function and structure
+
+* :history
+    > lists the entered expressions, numbered
+
+* :history (n)
+    > evaluates the expression number (n) again
+
+* :quit
+    > ends the REPL (so does the end of input)
+
+*/
+
+
+const PROMPT: &str = "miful> ";
+const CONTINUE_PROMPT: &str = "   ... ";
+
+const HELP: &str = ":help               lists the meta-commands
+:functions          lists the signatures of every defined function
+:type <expression>  prints the type of every value of <expression>
+:load <file>        evaluates <file>
+:reset              forgets every defined function and structure
+:history [n]        lists the entered expressions, or evaluates number [n] again
+:quit               ends the REPL
+";
+
+
+pub struct Repl<'a> {
+    driver: Driver<'a>,
+    renderer: Renderer,
+
+    history: Vec<String>,
+}
+
+impl<'a> Repl<'a> {
+    pub fn new(driver: Driver<'a>, renderer: Renderer) -> Repl<'a> {
+        Repl {
+            driver,
+            renderer,
+
+            history: vec![],
+        }
+    }
+
+    // [NOTE] Runs until `:quit` or the end of input.
+    //
+    pub fn run(&mut self) {
+        let mut buffer = String::new();
+
+        loop {
+            self.write(if buffer.is_empty() { PROMPT } else { CONTINUE_PROMPT });
+
+            let line = match self.driver.console().read_line() {
+                Ok(Some(line)) => { line },
+                _ => { break; },
+            };
+
+            if buffer.is_empty() && line.trim_start().starts_with(':') {
+                if !self.meta_command(line.trim()) {
+                    return;
+                }
+
+                continue;
+            }
+
+            buffer.push_str(&line);
+            buffer.push('\n');
+
+            if buffer.trim().is_empty() {
+                buffer.clear();
+
+            } else if !Repl::is_incomplete(&buffer) {
+                let source = buffer.trim_end().to_owned();
+
+                buffer.clear();
+
+                self.history.push(source.clone());
+                self.eval(&source, false);
+            }
+        }
+
+        // [NOTE] Reports what is left unclosed at the end of input.
+        //
+        if !buffer.trim().is_empty() {
+            self.eval(&buffer, false);
+        }
+
+        self.write("\n");
+    }
+
+    // [NOTE] Whether every error of `source` could be fixed by writing more of it.
+    //
+    fn is_incomplete(source: &str) -> bool {
+        let mut lexer = Lexer::new(segment_text(source), Driver::symbols());
+        let tokens = lexer.by_ref().collect();
+
+        let (_, parse_errors) = Parser::parse_all(tokens);
+        let errors: Vec<&MifulError> = lexer.errors().iter().chain(parse_errors.iter()).collect();
+
+        !errors.is_empty() && errors.iter().all(|e| {
+            let code = e.get_code();
+
+            code == codes::UNTERMINATED_STRUCTURE || code == codes::UNTERMINATED_UNQUOTE
+                || code == codes::UNTERMINATED_STRING || code == codes::UNTERMINATED_COMMENT
+        })
+    }
+
+    // [NOTE] Returns `false` if the REPL should end.
+    //
+    fn meta_command(&mut self, line: &str) -> bool {
+        let (command, arg) = match line.find(char::is_whitespace) {
+            Some(i) => { (&line[.. i], line[i ..].trim()) },
+            None => { (line, "") },
+        };
+
+        match command {
+            ":help" => { self.write(HELP); },
+
+            ":functions" => {
+                for sig in self.driver.signatures() {
+                    self.write(&format!("{}\n", sig));
+                }
+            },
+
+            ":type" if !arg.is_empty() => { self.eval(arg, true); },
+
+            ":load" if !arg.is_empty() => {
+                match fs::read_to_string(arg) {
+                    Ok(source) => { self.eval(&source, false); },
+                    Err(e) => { self.write_err(&format!("Cannot read ` {} `: {}\n", arg, e)); },
+                }
+            },
+
+            ":reset" => { self.driver.reset(); },
+
+            ":history" if arg.is_empty() => {
+                let listing: Vec<String> = self.history.iter()
+                    .enumerate()
+                    .map(|(i, source)| format!("{:>4}  {}\n", i + 1, source))
+                    .collect();
+
+                for entry in listing {
+                    self.write(&entry);
+                }
+            },
+
+            ":history" => {
+                match arg.parse::<usize>() {
+                    Ok(i) if i >= 1 && i <= self.history.len() => {
+                        let source = self.history[i - 1].clone();
+
+                        self.write(&format!("{}\n", source));
+
+                        self.history.push(source.clone());
+                        self.eval(&source, false);
+                    },
+
+                    _ => { self.write_err(&format!("No expression number ` {} ` in history!\n", arg)); },
+                }
+            },
+
+            ":quit" => { return false; },
+
+            _ => { self.write_err(&format!("Unknown command ` {} `, see :help\n", line)); },
+        }
+
+        true
+    }
+
+    // [NOTE] Prints every result except nil, or its type if `types` is set.
+    //
+    fn eval(&mut self, source: &str, types: bool) {
+        if let Err(e) = self.driver.feed(source) {
+            self.report(&e);
+
+            return;
+        }
+
+        while let Some(result) = self.driver.next() {
+            match result {
+                Ok(v) => {
+                    if types {
+                        let t = self.driver.type_of(&v);
+
+                        self.write(&format!("{}\n", t));
+
+                    } else if !is_nil(&v) {
+                        self.write(&format!("{}\n", v));
+                    }
+                },
+
+                Err(e) => {
+                    self.report(&e);
+
+                    return;
+                },
+            }
+        }
+    }
+
+    fn report(&self, e: &MifulError) {
+        self.write_err(&format!("{}\n", self.renderer.render(e)));
+    }
+
+    // [NOTE] A failing console leaves nothing to report to.
+    //
+    fn write(&self, s: &str) {
+        let _ = self.driver.console().write(s);
+        let _ = self.driver.console().flush();
+    }
+
+    fn write_err(&self, s: &str) {
+        let _ = self.driver.console().write_err(s);
+        let _ = self.driver.console().flush();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Repl;
+    use driver::Driver;
+    use console::Console;
+    use parsing::diagnostics::Renderer;
+
+    use std::cell::RefCell;
+    use std::io::{ self, Write };
+    use std::rc::Rc;
+
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // [NOTE] Runs the REPL over `input`, returning its output and error output.
+    //
+    fn run(input: &'static str) -> (String, String) {
+        let output = Rc::new(RefCell::new(vec![]));
+        let error = Rc::new(RefCell::new(vec![]));
+
+        let console = Console::new(Box::new(Shared(output.clone())), Box::new(input.as_bytes()))
+            .error_output(Box::new(Shared(error.clone())));
+
+        Repl::new(Driver::with_console("", console), Renderer::plain()).run();
+
+        let output = String::from_utf8(output.borrow().clone()).unwrap();
+        let error = String::from_utf8(error.borrow().clone()).unwrap();
+
+        (output, error)
+    }
+
+    #[test]
+    fn incomplete_input() {
+        assert!(Repl::is_incomplete("[+ 1"));
+        assert!(Repl::is_incomplete("{+ {? [: x]"));
+        assert!(Repl::is_incomplete("\"abc"));
+        assert!(Repl::is_incomplete("#| comment"));
+
+        assert!(!Repl::is_incomplete("[+ 1 2]"));
+        assert!(!Repl::is_incomplete("[+ 1 }"));
+        assert!(!Repl::is_incomplete("[+ 1 2]]"));
+    }
+
+    #[test]
+    fn keeps_definitions_and_continues_lines() {
+        let (output, error) = run("[define sq ((x int))\n  {* [: x] [: x]}]\n[sq 7]\n:functions\n");
+
+        assert!(error.is_empty(), "{}", error);
+        assert!(output.contains("\n49\n") || output.contains(" 49\n"), "{}", output);
+        assert!(output.contains("sq :: int"), "{}", output);
+    }
+
+    #[test]
+    fn errors_show_the_input_they_come_from() {
+        let (_, error) = run("[define g ((x int)) {/ [: x] 0}]\n[g 1]\n");
+
+        assert!(error.contains("1 | [define g ((x int)) {/ [: x] 0}]"), "{}", error);
+        assert!(!error.contains("| [g 1]"), "{}", error);
+    }
+}